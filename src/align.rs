@@ -0,0 +1,303 @@
+use std::collections::{HashMap, HashSet};
+
+use rkiwi::{Kiwi, Match, POSTag};
+use serde_json::{Value, json};
+use widestring::U16String;
+
+use crate::{Split, classify::EntryKind, normalize::normalize, segment::Side, series::base_title};
+
+/// 로마자 구와 함께 쓰인 한국어 형태소
+#[derive(Debug, Clone, PartialEq)]
+pub struct Alignment {
+    pub korean: String,
+    /// 함께 나온 제목 수
+    pub count: u32,
+    /// 다이스 계수
+    pub score: f32,
+}
+
+/// 로마자 단어나 구를 한국어 형태소에 대응시키는 표
+///
+/// 양쪽 언어가 모두 있는 제목에서 함께 나온 횟수로 만듦
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Table {
+    /// 표를 만드는 데 쓴 제목 수
+    pub pairs: u32,
+    /// 점수가 높은 순으로 정렬됨
+    phrases: HashMap<String, Vec<Alignment>>,
+}
+
+/// 구를 이루는 최대 단어 수
+const MAX_PHRASE_WORDS: usize = 2;
+/// 이보다 적게 함께 나온 것은 버림
+const MIN_COUNT: u32 = 2;
+const MIN_SCORE: f32 = 0.3;
+/// 구마다 남기는 형태소 수
+const MAX_CANDIDATES: usize = 5;
+
+/// 혼자서는 대응시키지 않는 조사
+pub const PARTICLES: [&str; 12] = [
+    "no", "to", "ni", "wa", "ga", "wo", "o", "de", "mo", "e", "ka", "ya",
+];
+
+/// 대응시킬 형태소의 품사
+const CONTENT_TAGS: [POSTag; 8] = [
+    POSTag::NNG,
+    POSTag::NNP,
+    POSTag::NR,
+    POSTag::NP,
+    POSTag::VV,
+    POSTag::VA,
+    POSTag::MAG,
+    POSTag::XR,
+];
+
+/// 작품 제목이고, 외국어와 한국어가 한 구간씩만 있는 것
+pub fn is_confident(split: &Split) -> bool {
+    split.classification.kind == EntryKind::Work
+        && !split.foreign.is_empty()
+        && !split.korean.is_empty()
+        && split.runs.len() == 2
+        && split.runs.iter().any(|x| x.side == Side::Foreign)
+        && split.runs.iter().any(|x| x.side == Side::Korean)
+}
+
+/// 에피소드를 지운 외국어 제목의 단어와 이어진 단어들
+pub fn phrases(split: &Split) -> Vec<String> {
+    let base = normalize(&base_title(&split.foreign, &split.episodes, Side::Foreign));
+    let words = base
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|x| !x.is_empty() && !x.chars().all(|c| c.is_ascii_digit()))
+        .collect::<Vec<_>>();
+
+    let mut res = Vec::new();
+    for n in 1..=MAX_PHRASE_WORDS {
+        for w in words.windows(n) {
+            if n == 1 && PARTICLES.contains(&w[0]) {
+                continue;
+            }
+            res.push(w.join(" "));
+        }
+    }
+
+    res
+}
+
+/// 한국어 문장의 내용 형태소와 `extra_tags` 품사의 형태소를 `normalize`로 바꿔 모음
+pub fn content_morphemes(
+    kiwi: &Kiwi,
+    korean: &str,
+    extra_tags: &[POSTag],
+    normalize: impl Fn(&str) -> String,
+) -> Result<Vec<String>, Box<dyn std::error::Error>> {
+    if korean.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let text = U16String::from_str(korean);
+    let analyzed = kiwi.analyze_w(
+        text.as_ustr(),
+        1,
+        Match::new().all_with_normailize_coda(),
+        None,
+        None,
+    )?;
+
+    Ok(analyzed
+        .to_vec_w()
+        .into_iter()
+        .filter(|(_, token)| CONTENT_TAGS.contains(&token.tag) || extra_tags.contains(&token.tag))
+        .map(|(form, _)| normalize(&form.to_string_lossy()))
+        .collect())
+}
+
+/// 에피소드를 지운 한국어 제목의 내용 형태소
+pub fn morphemes(kiwi: &Kiwi, split: &Split) -> Result<Vec<String>, Box<dyn std::error::Error>> {
+    let base = base_title(&split.korean, &split.episodes, Side::Korean);
+
+    content_morphemes(kiwi, &base, &[], normalize)
+}
+
+impl Table {
+    /// 믿을 만하게 나뉜 제목만 씀
+    pub fn build(
+        kiwi: &Kiwi,
+        items: &[(String, Split)],
+    ) -> Result<Self, Box<dyn std::error::Error>> {
+        let mut pairs = 0;
+        let mut foreign = HashMap::<String, u32>::new();
+        let mut korean = HashMap::<String, u32>::new();
+        let mut both = HashMap::<(String, String), u32>::new();
+
+        for (_, split) in items.iter().filter(|(_, x)| is_confident(x)) {
+            // 한 제목에서 여러 번 나와도 한 번으로 셈
+            let fs = phrases(split).into_iter().collect::<HashSet<_>>();
+            let ks = morphemes(kiwi, split)?.into_iter().collect::<HashSet<_>>();
+            if fs.is_empty() || ks.is_empty() {
+                continue;
+            }
+
+            pairs += 1;
+            for f in &fs {
+                *foreign.entry(f.clone()).or_default() += 1;
+            }
+            for k in &ks {
+                *korean.entry(k.clone()).or_default() += 1;
+            }
+            for f in &fs {
+                for k in &ks {
+                    *both.entry((f.clone(), k.clone())).or_default() += 1;
+                }
+            }
+        }
+
+        let mut phrases = HashMap::<String, Vec<Alignment>>::new();
+        for ((f, k), count) in both {
+            let score = 2.0 * count as f32 / (foreign[&f] + korean[&k]) as f32;
+            if count < MIN_COUNT || score < MIN_SCORE {
+                continue;
+            }
+
+            phrases.entry(f).or_default().push(Alignment {
+                korean: k,
+                count,
+                score,
+            });
+        }
+
+        for xs in phrases.values_mut() {
+            xs.sort_by(|a, b| {
+                b.score
+                    .total_cmp(&a.score)
+                    .then(b.count.cmp(&a.count))
+                    .then(a.korean.cmp(&b.korean))
+            });
+            xs.truncate(MAX_CANDIDATES);
+        }
+
+        Ok(Self { pairs, phrases })
+    }
+
+    /// 점수가 높은 순
+    pub fn get(&self, phrase: &str) -> &[Alignment] {
+        self.phrases
+            .get(&normalize(phrase))
+            .map_or(&[], Vec::as_slice)
+    }
+
+    /// 외국어 제목의 단어를 앞에서부터 가장 긴 구로 찾아 점수가 가장 높은 형태소로 바꿈
+    ///
+    /// 표에 없는 단어는 건너뜀
+    pub fn suggest(&self, foreign: &str) -> Vec<String> {
+        let base = normalize(foreign);
+        let words = base
+            .split(|c: char| !c.is_alphanumeric())
+            .filter(|x| !x.is_empty())
+            .collect::<Vec<_>>();
+
+        let mut res = Vec::new();
+        let mut i = 0;
+
+        while i < words.len() {
+            let found = (1..=MAX_PHRASE_WORDS.min(words.len() - i))
+                .rev()
+                .find_map(|n| Some((n, self.get(&words[i..i + n].join(" ")).first()?)));
+
+            match found {
+                Some((n, x)) => {
+                    res.push(x.korean.clone());
+                    i += n;
+                }
+                None => i += 1,
+            }
+        }
+
+        res
+    }
+
+    pub fn len(&self) -> usize {
+        self.phrases.len()
+    }
+
+    pub fn to_json(&self) -> Value {
+        let mut phrases = self.phrases.iter().collect::<Vec<_>>();
+        phrases.sort_by_key(|(phrase, _)| phrase.as_str());
+
+        json!({
+            "pairs": self.pairs,
+            "phrases": phrases
+                .into_iter()
+                .map(|(phrase, xs)| {
+                    let xs = xs
+                        .iter()
+                        .map(|x| json!({ "korean": x.korean, "count": x.count, "score": x.score }))
+                        .collect::<Vec<_>>();
+                    (phrase.clone(), Value::from(xs))
+                })
+                .collect::<serde_json::Map<_, _>>(),
+        })
+    }
+
+    /// `to_json`으로 만든 값을 읽음
+    pub fn from_json(value: &Value) -> Option<Self> {
+        let pairs = value.get("pairs")?.as_u64()? as u32;
+        let mut phrases = HashMap::new();
+
+        for (phrase, xs) in value.get("phrases")?.as_object()? {
+            let xs = xs
+                .as_array()?
+                .iter()
+                .map(|x| {
+                    Some(Alignment {
+                        korean: x.get("korean")?.as_str()?.to_owned(),
+                        count: x.get("count")?.as_u64()? as u32,
+                        score: x.get("score")?.as_f64()? as f32,
+                    })
+                })
+                .collect::<Option<Vec<_>>>()?;
+            phrases.insert(phrase.clone(), xs);
+        }
+
+        Some(Self { pairs, phrases })
+    }
+}
+
+#[test]
+fn test_build() -> Result<(), Box<dyn std::error::Error>> {
+    use rkiwi::KiwiBuilder;
+
+    let kiwi = KiwiBuilder::new(None, Default::default())?.build(None, None)?;
+
+    let items = [
+        "Kanojo to Natsuyasumi | 여자친구 여름방학",
+        "Kanojo no Himitsu 2 | 여자친구 비밀 2",
+        "Natsuyasumi no Himitsu | 여름방학 비밀",
+        "Kanojo Kaizou",
+        "Patreon Reward | 후원 보상",
+    ]
+    .into_iter()
+    .map(|x| Ok((x.to_owned(), crate::process(&kiwi, x)?)))
+    .collect::<Result<Vec<_>, Box<dyn std::error::Error>>>()?;
+
+    let table = Table::build(&kiwi, &items)?;
+    assert_eq!(table.pairs, 3);
+
+    let kanojo = table.get("Kanojo");
+    assert_eq!(kanojo[0].korean, "여자친구");
+    assert_eq!(kanojo[0].count, 2);
+    assert_eq!(kanojo.len(), 1);
+
+    assert_eq!(table.get("himitsu")[0].korean, "비밀");
+    assert!(table.get("no").is_empty());
+    assert!(table.get("kaizou").is_empty());
+
+    assert_eq!(
+        table.suggest("Kanojo no Natsuyasumi 3"),
+        ["여자친구", "여름방학"]
+    );
+
+    let loaded = Table::from_json(&table.to_json());
+    assert_eq!(loaded.as_ref(), Some(&table));
+
+    Ok(())
+}
@@ -0,0 +1,235 @@
+use std::ops::Range;
+
+use rkiwi::analyzed::Token;
+use widestring::U16String;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BracketKind {
+    /// `()` `（）`
+    Round,
+    /// `[]` `［］`
+    Square,
+    /// `{}` `｛｝`
+    Curly,
+    /// `<>` `＜＞` `〈〉` `《》`
+    Angle,
+    /// `「」` `｢｣`
+    Corner,
+    /// `『』`
+    WhiteCorner,
+    /// `【】` `〔〕` `〖〗`
+    Lenticular,
+}
+
+const BRACKETS: [(char, char, BracketKind); 16] = [
+    ('(', ')', BracketKind::Round),
+    ('（', '）', BracketKind::Round),
+    ('[', ']', BracketKind::Square),
+    ('［', '］', BracketKind::Square),
+    ('{', '}', BracketKind::Curly),
+    ('｛', '｝', BracketKind::Curly),
+    ('<', '>', BracketKind::Angle),
+    ('＜', '＞', BracketKind::Angle),
+    ('〈', '〉', BracketKind::Angle),
+    ('《', '》', BracketKind::Angle),
+    ('「', '」', BracketKind::Corner),
+    ('｢', '｣', BracketKind::Corner),
+    ('『', '』', BracketKind::WhiteCorner),
+    ('【', '】', BracketKind::Lenticular),
+    ('〔', '〕', BracketKind::Lenticular),
+    ('〖', '〗', BracketKind::Lenticular),
+];
+
+fn opening(c: char) -> Option<BracketKind> {
+    BRACKETS
+        .iter()
+        .find(|(o, _, _)| *o == c)
+        .map(|(_, _, k)| *k)
+}
+
+fn closing(c: char) -> Option<BracketKind> {
+    BRACKETS
+        .iter()
+        .find(|(_, e, _)| *e == c)
+        .map(|(_, _, k)| *k)
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Bracket {
+    /// 여는 괄호의 종류, 여는 괄호가 없으면 닫는 괄호의 종류
+    pub kind: BracketKind,
+    /// 여는 괄호의 토큰 위치
+    pub open: Option<usize>,
+    /// 닫는 괄호의 토큰 위치
+    pub close: Option<usize>,
+    /// 괄호를 포함한 원문 위치 (utf-16)
+    ///
+    /// 짝이 없는 괄호는 괄호 문자 자신만을 가리킴
+    pub span: Range<usize>,
+    /// `[korean}` 처럼 종류가 다른 괄호끼리 짝지어진 경우
+    pub mismatched: bool,
+    pub depth: usize,
+}
+
+impl Bracket {
+    pub fn is_matched(&self) -> bool {
+        self.open.is_some() && self.close.is_some()
+    }
+
+    /// 괄호 안쪽의 토큰 위치
+    pub fn inner(&self) -> Option<Range<usize>> {
+        Some(self.open? + 1..self.close?)
+    }
+}
+
+struct Open {
+    kind: BracketKind,
+    token: usize,
+    chr_position: usize,
+}
+
+/// 스택으로 괄호의 짝을 찾음
+///
+/// - 닫는 괄호와 같은 종류의 여는 괄호가 스택에 있으면, 그 사이에 열린 괄호들은 닫히지 않은 것으로 처리함
+/// - 같은 종류가 없으면 가장 안쪽의 여는 괄호와 짝지음 (`[korean}`)
+/// - 스택이 비어 있으면 짝이 없는 닫는 괄호로 처리함
+///
+/// 결과는 여는 위치 순으로 정렬됨
+pub fn match_brackets(xs: &[(U16String, Token)]) -> Vec<Bracket> {
+    let mut res = Vec::new();
+    let mut stack: Vec<Open> = Vec::new();
+
+    for (i, (form, token)) in xs.iter().enumerate() {
+        let mut chr_position = token.chr_position;
+
+        for c in char::decode_utf16(form.as_slice().iter().copied()) {
+            let Ok(c) = c else {
+                chr_position += 1;
+                continue;
+            };
+            let width = c.len_utf16();
+
+            if let Some(kind) = opening(c) {
+                stack.push(Open {
+                    kind,
+                    token: i,
+                    chr_position,
+                });
+            } else if let Some(kind) = closing(c) {
+                let found = stack.iter().rposition(|open| open.kind == kind);
+
+                let open = match found {
+                    Some(at) => {
+                        for (depth, unclosed) in stack.drain(at + 1..).enumerate() {
+                            res.push(Bracket {
+                                kind: unclosed.kind,
+                                open: Some(unclosed.token),
+                                close: None,
+                                span: unclosed.chr_position..unclosed.chr_position + 1,
+                                mismatched: false,
+                                depth: at + 1 + depth,
+                            });
+                        }
+                        stack.pop()
+                    }
+                    None => stack.pop(),
+                };
+
+                match open {
+                    Some(open) => res.push(Bracket {
+                        kind: open.kind,
+                        open: Some(open.token),
+                        close: Some(i),
+                        span: open.chr_position..chr_position + width,
+                        mismatched: open.kind != kind,
+                        depth: stack.len(),
+                    }),
+                    None => res.push(Bracket {
+                        kind,
+                        open: None,
+                        close: Some(i),
+                        span: chr_position..chr_position + width,
+                        mismatched: false,
+                        depth: 0,
+                    }),
+                }
+            }
+
+            chr_position += width;
+        }
+    }
+
+    for (depth, unclosed) in stack.into_iter().enumerate() {
+        res.push(Bracket {
+            kind: unclosed.kind,
+            open: Some(unclosed.token),
+            close: None,
+            span: unclosed.chr_position..unclosed.chr_position + 1,
+            mismatched: false,
+            depth,
+        });
+    }
+
+    res.sort_by_key(|x| (x.span.start, x.depth));
+
+    res
+}
+
+#[test]
+fn test_match_brackets() -> Result<(), Box<dyn std::error::Error>> {
+    use rkiwi::{KiwiBuilder, Match};
+
+    let kiwi = KiwiBuilder::new(None, Default::default())?.build(None, None)?;
+    let match_options = Match::new().all_with_normailize_coda();
+
+    let slice = |txt: &U16String, x: &Bracket| txt[x.span.clone()].to_string();
+
+    // nested
+    let txt = U16String::from_str("(C103) [Circle (Artist)] Title 「부제목」");
+    let xs = kiwi
+        .analyze_w(&txt, 1, match_options, None, None)?
+        .to_vec_w();
+    let res = match_brackets(&xs);
+    assert_eq!(res.len(), 4);
+    assert!(res.iter().all(|x| x.is_matched() && !x.mismatched));
+    assert_eq!(slice(&txt, &res[0])?, "(C103)");
+    assert_eq!(slice(&txt, &res[1])?, "[Circle (Artist)]");
+    assert_eq!(slice(&txt, &res[2])?, "(Artist)");
+    assert_eq!(res[2].depth, 1);
+    assert_eq!(res[3].kind, BracketKind::Corner);
+
+    // mismatched
+    let txt = U16String::from_str("일지~ [korean} Himitsu");
+    let xs = kiwi
+        .analyze_w(&txt, 1, match_options, None, None)?
+        .to_vec_w();
+    let res = match_brackets(&xs);
+    assert_eq!(res.len(), 1);
+    assert!(res[0].is_matched() && res[0].mismatched);
+    assert_eq!(slice(&txt, &res[0])?, "[korean}");
+
+    // unclosed and orphan
+    let txt = U16String::from_str("(foo [bar) baz] （qux");
+    let xs = kiwi
+        .analyze_w(&txt, 1, match_options, None, None)?
+        .to_vec_w();
+    let res = match_brackets(&xs);
+    assert_eq!(res.len(), 4);
+    assert_eq!(slice(&txt, &res[0])?, "(foo [bar)");
+    assert!(res[0].is_matched());
+    assert!(!res[1].is_matched() && res[1].close.is_none());
+    assert!(!res[2].is_matched() && res[2].open.is_none());
+    assert!(!res[3].is_matched() && res[3].kind == BracketKind::Round);
+
+    // `〖〗`
+    let txt = U16String::from_str("〖Himitsu〗");
+    let xs = kiwi
+        .analyze_w(&txt, 1, match_options, None, None)?
+        .to_vec_w();
+    let res = match_brackets(&xs);
+    assert_eq!(res.len(), 1);
+    assert!(res[0].is_matched() && !res[0].mismatched);
+    assert_eq!(res[0].kind, BracketKind::Lenticular);
+
+    Ok(())
+}
@@ -0,0 +1,200 @@
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EntryKind {
+    /// 작품 제목
+    Work,
+    /// `일러모음` `CG集` 같은 이미지셋
+    ImageSet,
+    /// `Patreon 2019/02~2025/02 Tier2 Reward` 같은 후원 보상
+    RewardPack,
+    /// `총집편` `総集編` 같은 모음
+    Compilation,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Classification {
+    pub kind: EntryKind,
+    /// 분류의 근거가 된 원문
+    pub evidence: Option<String>,
+}
+
+/// 먼저 나온 종류를 우선함
+///
+/// 라틴 문자로 된 단어는 이어진 단어 단위로, 나머지는 공백을 무시하고 부분 문자열로 비교함.
+/// `reward` `cg` 처럼 제목에도 쓰이는 단어는 홀로 쓰지 않음
+const KEYWORDS: [(&str, EntryKind); 28] = [
+    ("patreon", EntryKind::RewardPack),
+    ("fanbox", EntryKind::RewardPack),
+    ("fantia", EntryKind::RewardPack),
+    ("gumroad", EntryKind::RewardPack),
+    ("subscribestar", EntryKind::RewardPack),
+    ("후원", EntryKind::RewardPack),
+    ("支援", EntryKind::RewardPack),
+    ("일러모음", EntryKind::ImageSet),
+    ("일러스트모음", EntryKind::ImageSet),
+    ("일러스트집", EntryKind::ImageSet),
+    ("cg모음", EntryKind::ImageSet),
+    ("cg集", EntryKind::ImageSet),
+    ("画集", EntryKind::ImageSet),
+    ("イラスト集", EntryKind::ImageSet),
+    ("artbook", EntryKind::ImageSet),
+    ("artworks", EntryKind::ImageSet),
+    ("illustrations", EntryKind::ImageSet),
+    ("cg pack", EntryKind::ImageSet),
+    ("cg set", EntryKind::ImageSet),
+    ("cg collection", EntryKind::ImageSet),
+    ("총집편", EntryKind::Compilation),
+    ("모음집", EntryKind::Compilation),
+    ("합본", EntryKind::Compilation),
+    ("総集編", EntryKind::Compilation),
+    ("soushuuhen", EntryKind::Compilation),
+    ("compilation", EntryKind::Compilation),
+    ("omnibus", EntryKind::Compilation),
+    ("anthology", EntryKind::Compilation),
+];
+
+const RANGE_CHARS: [char; 3] = ['~', '～', '-'];
+
+fn is_latin(x: &str) -> bool {
+    x.chars().all(|c| c.is_ascii_alphanumeric() || c == ' ')
+}
+
+fn is_number(x: &str) -> bool {
+    !x.is_empty() && x.chars().all(|c| c.is_ascii_digit())
+}
+
+/// `Tier2 Reward` `Tier 3 Rewards`
+fn tier_reward(words: &[&str]) -> Option<String> {
+    (0..words.len()).find_map(|i| {
+        let tier = words[i].strip_prefix("tier")?;
+        let next = if is_number(tier) {
+            i + 1
+        } else if tier.is_empty() && is_number(words.get(i + 1)?) {
+            i + 2
+        } else {
+            return None;
+        };
+
+        matches!(words.get(next), Some(&("reward" | "rewards"))).then(|| words[i..=next].join(" "))
+    })
+}
+
+/// `2019/02` `2023.01` `2024-12` 처럼 연도와 월로 된 날짜의 (시작, 끝) 위치
+fn year_months(x: &[char]) -> Vec<(usize, usize)> {
+    let mut res = Vec::new();
+    let mut i = 0;
+
+    while i + 6 <= x.len() {
+        let year = x[i..i + 4].iter().all(char::is_ascii_digit)
+            && (i == 0 || !x[i - 1].is_ascii_digit())
+            && ['/', '.', '-'].contains(&x[i + 4]);
+
+        let month = x[i + 5..].iter().take_while(|c| c.is_ascii_digit()).count();
+
+        if year && (1..=2).contains(&month) {
+            res.push((i, i + 5 + month));
+            i += 5 + month;
+        } else {
+            i += 1;
+        }
+    }
+
+    res
+}
+
+/// `2019/02~2025/02`
+fn date_range(text: &str) -> Option<String> {
+    let x = text.chars().collect::<Vec<_>>();
+
+    year_months(&x).windows(2).find_map(|w| {
+        let ((start, a), (b, end)) = (w[0], w[1]);
+        let between = x[a..b].iter().collect::<String>();
+
+        RANGE_CHARS
+            .contains(&between.trim().chars().next()?)
+            .then(|| x[start..end].iter().collect())
+    })
+}
+
+/// 작품 제목인지, 이미지셋이나 후원 보상, 모음인지 분류함
+pub fn classify(text: &str) -> Classification {
+    let lowercase = text.to_lowercase();
+    let words = lowercase
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|x| !x.is_empty())
+        .collect::<Vec<_>>();
+    let compact = lowercase
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .collect::<String>();
+
+    let found = KEYWORDS.iter().find(|(keyword, _)| {
+        if is_latin(keyword) {
+            let keyword = keyword.split(' ').collect::<Vec<_>>();
+            words.windows(keyword.len()).any(|x| x == keyword)
+        } else {
+            compact.contains(keyword)
+        }
+    });
+
+    if let Some((keyword, kind)) = found {
+        return Classification {
+            kind: *kind,
+            evidence: Some(keyword.to_string()),
+        };
+    }
+
+    if let Some(x) = tier_reward(&words) {
+        return Classification {
+            kind: EntryKind::RewardPack,
+            evidence: Some(x),
+        };
+    }
+
+    // 기간이 붙은 것은 대부분 후원 보상임
+    if let Some(range) = date_range(text) {
+        return Classification {
+            kind: EntryKind::RewardPack,
+            evidence: Some(range),
+        };
+    }
+
+    Classification {
+        kind: EntryKind::Work,
+        evidence: None,
+    }
+}
+
+#[test]
+fn test_classify() {
+    let kind = |x| classify(x).kind;
+
+    assert_eq!(
+        kind("Patreon 2019/02~2025/02 Tier2 Reward"),
+        EntryKind::RewardPack
+    );
+    assert_eq!(kind("Artist 2023.01 - 2023.06"), EntryKind::RewardPack);
+    assert_eq!(kind("KissNTR.Gold 3.일러모음"), EntryKind::ImageSet);
+    assert_eq!(kind("Kemomimi CG集"), EntryKind::ImageSet);
+    assert_eq!(kind("Seiso Kanojo Soushuuhen"), EntryKind::Compilation);
+
+    assert_eq!(kind("Frontier Girls"), EntryKind::Work);
+    assert_eq!(kind("철혈 M16"), EntryKind::Work);
+    assert_eq!(kind("Pokemon SV MTR | 포켓몬 SV MTR 6-7"), EntryKind::Work);
+
+    // 제목에도 쓰이는 단어는 홀로 있으면 작품으로 봄
+    assert_eq!(kind("Yuusha no Reward"), EntryKind::Work);
+    assert_eq!(kind("Onee-san no Rewards | 누나의 보상"), EntryKind::Work);
+    assert_eq!(kind("Isekai Harem CG"), EntryKind::Work);
+    assert_eq!(kind("Tier Reward"), EntryKind::Work);
+
+    let res = classify("Artist Tier 3 Rewards");
+    assert_eq!(res.kind, EntryKind::RewardPack);
+    assert_eq!(res.evidence.as_deref(), Some("tier 3 rewards"));
+    assert_eq!(kind("Kemomimi CG Pack"), EntryKind::ImageSet);
+    assert_eq!(kind("Kemomimi CG모음"), EntryKind::ImageSet);
+
+    assert_eq!(
+        date_range("Patreon 2019/02~2025/02").as_deref(),
+        Some("2019/02~2025/02")
+    );
+}
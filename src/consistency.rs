@@ -0,0 +1,357 @@
+use std::collections::HashSet;
+
+use rkiwi::Kiwi;
+
+use crate::{
+    Split,
+    align::{self, PARTICLES, Table},
+    normalize::normalize,
+    romaji::{self, hangul},
+    segment::Side,
+    series::base_title,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Verdict {
+    /// 한국어 제목이 외국어 제목을 소리대로 옮긴 것
+    Transliteration,
+    /// 한국어 제목이 외국어 제목을 번역한 것
+    Translation,
+    /// 다른 작품의 제목이거나 잘못 나뉜 것
+    Mismatch,
+    /// 판단할 근거가 없음
+    Unknown,
+}
+
+/// 외국어 제목과 한국어 제목이 같은 작품을 가리키는지
+#[derive(Debug, Clone, PartialEq)]
+pub struct Consistency {
+    /// 한국어 제목에 소리대로 옮겨진 외국어 단어의 비율
+    pub transliteration: f32,
+    /// 양쪽 에피소드가 같은지, 한쪽에만 있으면 `None`
+    pub numbers: Option<bool>,
+    /// 한국어 제목의 라틴 문자 단어 중 외국어 제목에도 있는 것의 비율
+    pub latin: Option<f32>,
+    /// 대응표에 있는 외국어 구 중 대응하는 한국어 형태소가 있는 것의 비율
+    pub alignment: Option<f32>,
+    /// 음역을 제외한 근거들의 가중 평균과 음역 비율 중 큰 것 (0 ~ 1)
+    pub score: f32,
+    pub verdict: Verdict,
+}
+
+/// 이보다 많은 단어가 소리대로 옮겨졌으면 음역으로 봄
+const TRANSLITERATION_RATIO: f32 = 0.5;
+/// 이보다 점수가 낮으면 다른 작품으로 봄
+const MIN_SCORE: f32 = 0.5;
+/// 자음 골격이 이만큼 같으면 소리대로 옮긴 것으로 봄 (`IDOLM@STER` `아이돌마스터`)
+const MIN_SKELETON_SIMILARITY: f32 = 0.8;
+const MIN_SKELETON_LEN: usize = 3;
+
+const ALIGNMENT_WEIGHT: f32 = 2.0;
+
+/// 로마자의 자음 골격, 소리가 비슷한 자음은 하나로 맞춤
+fn latin_skeleton(x: &str) -> Vec<char> {
+    let x = x
+        .chars()
+        .filter(char::is_ascii_alphabetic)
+        .collect::<Vec<_>>();
+    let mut res = Vec::new();
+    let mut i = 0;
+
+    while i < x.len() {
+        let pair = (x[i], x.get(i + 1).copied());
+        let (c, len) = match pair {
+            ('c', Some('h')) => (Some('j'), 2),
+            ('s', Some('h')) | ('t', Some('s')) => (Some('s'), 2),
+            ('b' | 'p' | 'f' | 'v', _) => (Some('p'), 1),
+            ('d' | 't', _) => (Some('t'), 1),
+            ('g' | 'k' | 'c' | 'q', _) => (Some('k'), 1),
+            ('l' | 'r', _) => (Some('l'), 1),
+            ('z' | 'j', _) => (Some('j'), 1),
+            ('m' | 'n' | 's' | 'h', _) => (Some(x[i]), 1),
+            _ => (None, 1),
+        };
+
+        res.extend(c);
+        i += len;
+    }
+
+    res
+}
+
+/// 한글의 자음 골격
+fn hangul_skeleton(x: &str) -> Vec<char> {
+    const INITIALS: [Option<char>; 19] = [
+        Some('k'),
+        Some('k'),
+        Some('n'),
+        Some('t'),
+        Some('t'),
+        Some('l'),
+        Some('m'),
+        Some('p'),
+        Some('p'),
+        Some('s'),
+        Some('s'),
+        None,
+        Some('j'),
+        Some('j'),
+        Some('j'),
+        Some('k'),
+        Some('t'),
+        Some('p'),
+        Some('h'),
+    ];
+
+    let mut res = Vec::new();
+
+    for c in x.chars() {
+        let Some(x) = (c as u32).checked_sub(0xAC00).filter(|x| *x < 11172) else {
+            continue;
+        };
+
+        res.extend(INITIALS[(x / 28 / 21) as usize]);
+        res.extend(match x % 28 {
+            1 => Some('k'),
+            4 => Some('n'),
+            8 => Some('l'),
+            16 => Some('m'),
+            17 => Some('p'),
+            19 => Some('t'),
+            _ => None,
+        });
+    }
+
+    res
+}
+
+/// 최장 공통 부분 수열의 길이를 긴 쪽의 길이로 나눔
+fn similarity(a: &[char], b: &[char]) -> f32 {
+    let len = a.len().max(b.len());
+    if len == 0 {
+        return 0.0;
+    }
+
+    let mut prev = vec![0; b.len() + 1];
+    for x in a {
+        let mut cur = vec![0; b.len() + 1];
+        for (j, y) in b.iter().enumerate() {
+            cur[j + 1] = if x == y {
+                prev[j] + 1
+            } else {
+                cur[j].max(prev[j + 1])
+            };
+        }
+        prev = cur;
+    }
+
+    prev[b.len()] as f32 / len as f32
+}
+
+/// 외국어 단어가 한국어 단어 중 하나에 소리대로 옮겨졌는지
+fn transliterated(word: &str, korean: &[String]) -> bool {
+    let variants = romaji::variants(word)
+        .iter()
+        .map(|x| hangul(x))
+        .filter(|x| x.chars().count() >= 2)
+        .collect::<Vec<_>>();
+    let skeleton = latin_skeleton(word);
+
+    korean.iter().any(|k| {
+        variants.iter().any(|x| k.contains(x.as_str()))
+            || (skeleton.len() >= MIN_SKELETON_LEN
+                && similarity(&skeleton, &hangul_skeleton(k)) >= MIN_SKELETON_SIMILARITY)
+    })
+}
+
+/// 한쪽 언어의 원래 에피소드
+fn own_episodes(split: &Split, side: Side) -> HashSet<(u32, u32)> {
+    split
+        .episodes
+        .iter()
+        .filter(|x| x.side == side && x.origin == side)
+        .map(|x| (x.start, x.end))
+        .collect()
+}
+
+/// 외국어와 한국어가 모두 있는 제목의 두 부분이 같은 작품을 가리키는지 확인함
+///
+/// 음역, 에피소드, 라틴 문자 단어, 대응표를 근거로 씀
+pub fn check(
+    kiwi: &Kiwi,
+    split: &Split,
+    table: &Table,
+) -> Result<Consistency, Box<dyn std::error::Error>> {
+    let foreign = normalize(&base_title(&split.foreign, &split.episodes, Side::Foreign));
+    let korean = normalize(&base_title(&split.korean, &split.episodes, Side::Korean));
+
+    let words = foreign
+        .split_whitespace()
+        .filter(|x| x.chars().any(|c| c.is_ascii_alphabetic()) && !PARTICLES.contains(x))
+        .collect::<Vec<_>>();
+    let korean_words = korean
+        .split_whitespace()
+        .map(hangul)
+        .filter(|x| !x.is_empty())
+        .collect::<Vec<_>>();
+
+    // 제목 전체를 소리대로 옮긴 것은 나눌 때 이미 확인함
+    let transliteration = if split.transliterated {
+        1.0
+    } else if words.is_empty() {
+        0.0
+    } else {
+        let n = words
+            .iter()
+            .filter(|x| transliterated(x, &korean_words))
+            .count();
+        n as f32 / words.len() as f32
+    };
+
+    let (foreign_episodes, korean_episodes) = (
+        own_episodes(split, Side::Foreign),
+        own_episodes(split, Side::Korean),
+    );
+    let numbers = (!foreign_episodes.is_empty() && !korean_episodes.is_empty())
+        .then(|| foreign_episodes == korean_episodes);
+
+    let foreign_words = foreign
+        .split(|c: char| !c.is_alphanumeric())
+        .collect::<HashSet<_>>();
+    let latin = korean
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|x| x.chars().any(|c| c.is_ascii_alphabetic()))
+        .collect::<Vec<_>>();
+    let latin = (!latin.is_empty()).then(|| {
+        let n = latin.iter().filter(|x| foreign_words.contains(*x)).count();
+        n as f32 / latin.len() as f32
+    });
+
+    let morphemes = align::morphemes(kiwi, split)?
+        .into_iter()
+        .collect::<HashSet<_>>();
+    let known = align::phrases(split)
+        .into_iter()
+        .map(|x| table.get(&x))
+        .filter(|x| !x.is_empty())
+        .collect::<Vec<_>>();
+    let alignment = (!known.is_empty()).then(|| {
+        let n = known
+            .iter()
+            .filter(|xs| xs.iter().any(|x| morphemes.contains(&x.korean)))
+            .count();
+        n as f32 / known.len() as f32
+    });
+
+    // 음역은 번역일 수도 있으므로 점수를 올리는 데에만 씀
+    let signals = [
+        numbers.map(|x| (if x { 1.0 } else { 0.0 }, 1.0)),
+        latin.map(|x| (x, 1.0)),
+        alignment.map(|x| (x, ALIGNMENT_WEIGHT)),
+    ];
+    let weight = signals.iter().flatten().map(|(_, w)| w).sum::<f32>();
+    let score = if weight > 0.0 {
+        let mean = signals.iter().flatten().map(|(x, w)| x * w).sum::<f32>() / weight;
+        mean.max(transliteration)
+    } else {
+        transliteration
+    };
+
+    // 에피소드가 다르면 같은 제목이라도 다른 권임
+    let verdict = if numbers == Some(false) {
+        Verdict::Mismatch
+    } else if transliteration >= TRANSLITERATION_RATIO {
+        Verdict::Transliteration
+    } else if weight == 0.0 {
+        Verdict::Unknown
+    } else if score >= MIN_SCORE {
+        Verdict::Translation
+    } else {
+        Verdict::Mismatch
+    };
+
+    Ok(Consistency {
+        transliteration,
+        numbers,
+        latin,
+        alignment,
+        score,
+        verdict,
+    })
+}
+
+#[test]
+fn test_skeleton() {
+    assert_eq!(latin_skeleton("idolm@ster"), ['t', 'l', 'm', 's', 't', 'l']);
+    assert_eq!(hangul_skeleton("아이돌마스터"), ['t', 'l', 'm', 's', 't']);
+    assert!(
+        similarity(
+            &latin_skeleton("idolm@ster"),
+            &hangul_skeleton("아이돌마스터")
+        ) >= MIN_SKELETON_SIMILARITY
+    );
+}
+
+#[test]
+fn test_check() -> Result<(), Box<dyn std::error::Error>> {
+    use rkiwi::KiwiBuilder;
+
+    let kiwi = KiwiBuilder::new(None, Default::default())?.build(None, None)?;
+    let split = |x| crate::process(&kiwi, x);
+
+    let table = Table::build(
+        &kiwi,
+        &[
+            "Kareshi no Himitsu | 남친 비밀",
+            "Kareshi Mochi Joshi | 남친 있는 여자",
+            "Haishin Joshi | 방송 여자",
+            "Yoru no Haishin | 밤 방송",
+        ]
+        .into_iter()
+        .map(|x| Ok((x.to_owned(), split(x)?)))
+        .collect::<Result<Vec<_>, Box<dyn std::error::Error>>>()?,
+    )?;
+
+    let res = check(
+        &kiwi,
+        &split("Gakuen IDOLM@STER Fundoshi Goudou | 학원 아이돌마스터 훈도시 합동")?,
+        &table,
+    )?;
+    assert_eq!(res.transliteration, 0.5);
+    assert_eq!(res.verdict, Verdict::Transliteration);
+
+    let res = check(&kiwi, &split("Hamechichi! | 하메찌찌!")?, &table)?;
+    assert_eq!(res.transliteration, 1.0);
+    assert_eq!(res.verdict, Verdict::Transliteration);
+
+    let res = check(&kiwi, &split("Pokemon SV MTR 6 | 포켓몬 SV MTR 6")?, &table)?;
+    assert_eq!(res.numbers, Some(true));
+    assert_eq!(res.latin, Some(1.0));
+    assert_ne!(res.verdict, Verdict::Mismatch);
+
+    let txt = "남친 있는 백인 코스어, 공개 엉덩이 구멍 확장 방송 / Kareshi Mochi Hakujin Layer, Koukai Ketsuana Kakuchou Haishin";
+    let res = check(&kiwi, &split(txt)?, &Table::default())?;
+    assert_eq!(res.verdict, Verdict::Unknown);
+    let res = check(&kiwi, &split(txt)?, &table)?;
+    assert_eq!(res.alignment, Some(1.0));
+    assert_eq!(res.verdict, Verdict::Translation);
+
+    let res = check(
+        &kiwi,
+        &split("Kareshi no Himitsu 3 | 여름방학 이야기 5")?,
+        &table,
+    )?;
+    assert_eq!(res.numbers, Some(false));
+    assert_eq!(res.verdict, Verdict::Mismatch);
+
+    let res = check(
+        &kiwi,
+        &split("Kareshi no Himitsu 3 | 카레시 노 히미츠 5")?,
+        &table,
+    )?;
+    assert!(res.transliteration >= TRANSLITERATION_RATIO);
+    assert_eq!(res.numbers, Some(false));
+    assert_eq!(res.verdict, Verdict::Mismatch);
+
+    Ok(())
+}
@@ -0,0 +1,175 @@
+use std::ops::Range;
+
+use rkiwi::analyzed::Token;
+use widestring::{U16Str, U16String};
+
+use crate::{
+    bracket::{Bracket, BracketKind},
+    event::{Event, Events},
+    segment::glued,
+    tag,
+};
+
+/// 제목 앞에 붙은 `(C103) [Circle (Artist)]`
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Credit {
+    /// `(C103)`
+    pub event: Option<Event>,
+    pub circle: Option<String>,
+    pub artists: Vec<String>,
+    /// 제목 앞에서 차지하는 토큰 위치
+    pub tokens: Range<usize>,
+}
+
+const ARTIST_SEPARATORS: [char; 6] = [',', '，', '、', '&', '＆', '/'];
+
+fn inner_of(text: &U16Str, bracket: &Bracket) -> String {
+    text[bracket.span.start + 1..bracket.span.end - 1]
+        .to_string_lossy()
+        .trim()
+        .to_owned()
+}
+
+fn split_artists(x: &str) -> Vec<String> {
+    x.split(ARTIST_SEPARATORS)
+        .map(str::trim)
+        .filter(|x| !x.is_empty())
+        .map(str::to_owned)
+        .collect()
+}
+
+/// 제목 앞에 이어진 괄호 묶음에서 행사, 서클, 작가를 찾음
+///
+/// - `[Circle (Artist)]` 서클과 작가
+/// - `[Artist]` 작가만
+/// - `(C103) [Circle]` 처럼 대괄호 앞에 있는 소괄호는 행사
+/// - `(C97) Title` 처럼 대괄호가 없어도 알려진 행사면 행사
+///
+/// 뒤에 제목이 없거나 `[Korean]` 같은 태그면 제목 앞 묶음으로 보지 않음
+pub fn leading(
+    text: &U16Str,
+    xs: &[(U16String, Token)],
+    brackets: &[Bracket],
+    events: &Events,
+) -> Credit {
+    let top = |i: usize| {
+        brackets
+            .iter()
+            .find(|x| x.depth == 0 && x.is_matched() && !x.mismatched && x.open == Some(i))
+    };
+    let is_tag = |x: &Bracket| tag::parse(&inner_of(text, x)).is_some();
+
+    let mut i = 0;
+    let mut event = None;
+
+    if let Some(round) = top(0).filter(|x| x.kind == BracketKind::Round && !is_tag(x)) {
+        let next = round.close.unwrap_or_default() + 1;
+        let inner = inner_of(text, round);
+
+        // 알려진 행사가 아니면 바로 뒤에 대괄호 묶음이 있을 때만 행사로 봄
+        match events.parse(&inner) {
+            Some(x) => event = Some(x),
+            None if top(next).is_some_and(|x| x.kind == BracketKind::Square) => {
+                event = Some(Event::unknown(&inner))
+            }
+            None => {}
+        }
+
+        if event.is_some() {
+            i = next;
+        }
+    }
+
+    let square = top(i).filter(|x| x.kind == BracketKind::Square && !is_tag(x));
+    let end = match square {
+        Some(square) => square.close.unwrap_or_default() + 1,
+        None => i,
+    };
+
+    if end == 0 {
+        return Credit::default();
+    }
+
+    // 제목이 남아 있어야 하고, 대괄호 바로 뒤에 붙은 글자가 없어야 함
+    match xs.get(end) {
+        Some((_, t)) if !glued(&xs[end - 1].1, t) => {}
+        _ => return Credit::default(),
+    }
+
+    let Some(square) = square else {
+        return Credit {
+            event,
+            tokens: 0..end,
+            ..Default::default()
+        };
+    };
+
+    let nested = brackets.iter().find(|x| {
+        x.depth == 1
+            && x.kind == BracketKind::Round
+            && x.is_matched()
+            && square.span.start < x.span.start
+            && x.span.end < square.span.end
+    });
+
+    let (circle, artists) = match nested {
+        Some(nested) => {
+            let circle = text[square.span.start + 1..nested.span.start]
+                .to_string_lossy()
+                .trim()
+                .to_owned();
+
+            (
+                Some(circle).filter(|x| !x.is_empty()),
+                split_artists(&inner_of(text, nested)),
+            )
+        }
+        None => (None, split_artists(&inner_of(text, square))),
+    };
+
+    Credit {
+        event,
+        circle,
+        artists,
+        tokens: 0..end,
+    }
+}
+
+#[test]
+fn test_leading() -> Result<(), Box<dyn std::error::Error>> {
+    use rkiwi::{KiwiBuilder, Match};
+
+    let kiwi = KiwiBuilder::new(None, Default::default())?.build(None, None)?;
+    let match_options = Match::new().all_with_normailize_coda();
+
+    let leading = |txt: &str| -> Result<Credit, Box<dyn std::error::Error>> {
+        let txt = U16String::from_str(txt);
+        let xs = kiwi
+            .analyze_w(&txt, 1, match_options, None, None)?
+            .to_vec_w();
+        let brackets = crate::bracket::match_brackets(&xs);
+        Ok(leading(&txt, &xs, &brackets, &Events::default()))
+    };
+
+    let res = leading("(C103) [Circle (Artist A, Artist B)] Title 「부제목」")?;
+    assert_eq!(res.event.map(|x| x.number), Some(Some(103)));
+    assert_eq!(res.circle.as_deref(), Some("Circle"));
+    assert_eq!(res.artists, ["Artist A", "Artist B"]);
+
+    let res = leading("[Artist] Title")?;
+    assert_eq!(res.circle, None);
+    assert_eq!(res.artists, ["Artist"]);
+    assert_eq!(res.tokens, 0..3);
+
+    // 태그, 제목 없음
+    assert_eq!(leading("[Korean] Title")?, Credit::default());
+    assert_eq!(leading("[Circle (Artist)]")?, Credit::default());
+    assert_eq!(leading("(Blue Archive) Title")?, Credit::default());
+
+    // 대괄호 없는 행사
+    let res = leading("(COMIC1☆15) Title")?;
+    assert_eq!(res.event.map(|x| x.name), Some("COMIC1".to_owned()));
+    assert_eq!(res.tokens.start, 0);
+
+    Ok(())
+}
@@ -0,0 +1,534 @@
+use std::collections::{BTreeSet, HashMap, VecDeque};
+
+use serde_json::{Value, json};
+
+use crate::{
+    Split, index::Index, normalize::normalize, segment::Side, series::root, tfidf::SparseVector,
+    trim::TrimReason,
+};
+
+/// 중복으로 볼 때 무시한 차이
+#[derive(Debug, Clone, PartialEq)]
+pub enum Difference {
+    /// 띄어쓰기, 대소문자, 문장 부호, 장식 문자
+    Spelling,
+    /// `[Korean]` `(Decensored)` 같은 태그
+    Tags(Vec<String>),
+    /// 제목 앞의 행사, 서클, 작가
+    Credit,
+    /// `(Blue Archive)` 같은 원작 표기
+    Parody,
+    /// 가장자리에 남은 구분자나 짝이 없는 괄호
+    Trimmed(Vec<String>),
+    /// 이 언어의 제목이 한쪽에만 있음
+    MissingSide(Side),
+    /// 이 언어의 제목이 양쪽에 있지만 키가 다름
+    Title(Side),
+}
+
+/// 두 제목을 묶은 이유
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Reason {
+    /// 이 언어의 키가 같음
+    Key(Side),
+    /// 에피소드가 같고 벡터의 유사도가 기준 이상임
+    Similar(f32),
+}
+
+/// 직접 묶인 두 제목
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Link {
+    pub from: usize,
+    pub to: usize,
+    pub reason: Reason,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Duplicate {
+    /// 입력에서의 위치
+    pub index: usize,
+    pub title: String,
+    /// 대표 제목에서 이 제목까지 묶인 경로
+    pub links: Vec<Link>,
+    /// 대표 제목과의 차이
+    pub differences: Vec<Difference>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Group {
+    /// 대표 제목의 입력에서의 위치
+    pub canonical: usize,
+    pub title: String,
+    /// 대표 제목을 제외한 중복, 입력 순
+    pub duplicates: Vec<Duplicate>,
+}
+
+/// 벡터로 찾을 이웃 수
+const NEIGHBORS: usize = 10;
+
+/// 문자와 숫자만 남긴 정규화한 제목
+///
+/// 띄어쓰기와 문장 부호만 다른 제목은 같은 키를 가짐
+pub fn key(x: &str) -> String {
+    normalize(x)
+        .chars()
+        .filter(|c| c.is_alphanumeric())
+        .collect()
+}
+
+/// 키에는 숫자가 남지만 벡터는 숫자를 가볍게 보므로 따로 비교함
+fn episodes(split: &Split) -> BTreeSet<(u32, u32)> {
+    split.episodes.iter().map(|x| (x.start, x.end)).collect()
+}
+
+/// 대표 제목으로 양쪽 언어가 있고, 태그와 제작 정보가 많고, 먼저 나온 것을 고름
+fn canonical(items: &[(String, Split)], members: &[usize]) -> usize {
+    let rank = |i: usize| {
+        let x = &items[i].1;
+        let both = !x.foreign.is_empty() && !x.korean.is_empty();
+        let info = x.release.tags.len()
+            + x.event.is_some() as usize
+            + x.circle.is_some() as usize
+            + x.artists.len()
+            + x.parody.is_some() as usize;
+
+        (both, info, std::cmp::Reverse(i))
+    };
+
+    members.iter().copied().max_by_key(|i| rank(*i)).unwrap()
+}
+
+/// 한쪽에만 있는 것
+fn symmetric_difference(a: Vec<String>, b: Vec<String>) -> Vec<String> {
+    let (a, b) = (
+        a.into_iter().collect::<BTreeSet<_>>(),
+        b.into_iter().collect::<BTreeSet<_>>(),
+    );
+
+    a.symmetric_difference(&b).cloned().collect()
+}
+
+fn differences(a: &Split, b: &Split) -> Vec<Difference> {
+    let mut res = Vec::new();
+
+    let sides = [
+        (Side::Foreign, &a.foreign, &b.foreign),
+        (Side::Korean, &a.korean, &b.korean),
+    ];
+
+    let mut spelling = false;
+    for (side, x, y) in sides {
+        if x.is_empty() != y.is_empty() {
+            res.push(Difference::MissingSide(side));
+        } else if x.is_empty() {
+            continue;
+        } else if key(x) == key(y) {
+            spelling |= x != y;
+        } else {
+            res.push(Difference::Title(side));
+        }
+    }
+    if spelling {
+        res.push(Difference::Spelling);
+    }
+
+    let tags = |x: &Split| {
+        x.release
+            .tags
+            .iter()
+            .map(|x| x.text.to_lowercase())
+            .collect()
+    };
+    let tags = symmetric_difference(tags(a), tags(b));
+    if !tags.is_empty() {
+        res.push(Difference::Tags(tags));
+    }
+
+    if (&a.event, &a.circle, &a.artists) != (&b.event, &b.circle, &b.artists) {
+        res.push(Difference::Credit);
+    }
+
+    if a.parody.as_ref().map(|x| &x.name) != b.parody.as_ref().map(|x| &x.name) {
+        res.push(Difference::Parody);
+    }
+
+    // 태그는 위에서 다룸
+    let trimmed = |x: &Split| {
+        x.trimmed
+            .iter()
+            .filter(|x| matches!(x.reason, TrimReason::Separator | TrimReason::OrphanBracket))
+            .map(|x| x.text.clone())
+            .collect()
+    };
+    let trimmed = symmetric_difference(trimmed(a), trimmed(b));
+    if !trimmed.is_empty() {
+        res.push(Difference::Trimmed(trimmed));
+    }
+
+    res
+}
+
+/// 대표 제목에서 각 제목까지 묶인 경로
+fn paths(canonical: usize, links: &[Link]) -> HashMap<usize, Vec<Link>> {
+    let mut res = HashMap::from([(canonical, Vec::new())]);
+    let mut queue = VecDeque::from([canonical]);
+
+    while let Some(i) = queue.pop_front() {
+        for link in links {
+            let next = match (link.from == i, link.to == i) {
+                (true, _) => link.to,
+                (_, true) => link.from,
+                _ => continue,
+            };
+            if res.contains_key(&next) {
+                continue;
+            }
+
+            let mut path = res[&i].clone();
+            path.push(Link {
+                from: i,
+                to: next,
+                reason: link.reason,
+            });
+            res.insert(next, path);
+            queue.push_back(next);
+        }
+    }
+
+    res
+}
+
+fn title(split: &Split, side: Side) -> &str {
+    match side {
+        Side::Foreign => &split.foreign,
+        Side::Korean => &split.korean,
+    }
+}
+
+/// 묶음마다 언어별 키를 모아 두는 union-find
+struct Groups {
+    parent: Vec<usize>,
+    /// 외국어, 한국어 순. 묶음의 대표 위치에만 남김
+    keys: Vec<[BTreeSet<String>; 2]>,
+    /// 서로 다른 묶음을 처음 합친 것만 남기므로 묶음마다 나무가 됨
+    links: Vec<Link>,
+}
+
+impl Groups {
+    fn new(items: &[(String, Split)]) -> Self {
+        let keys = items
+            .iter()
+            .map(|(_, x)| {
+                [Side::Foreign, Side::Korean].map(|side| {
+                    Some(key(title(x, side)))
+                        .filter(|x| !x.is_empty())
+                        .into_iter()
+                        .collect()
+                })
+            })
+            .collect();
+
+        Self {
+            parent: (0..items.len()).collect(),
+            keys,
+            links: Vec::new(),
+        }
+    }
+
+    /// 반대쪽 언어의 키가 두 묶음에 모두 있는데 겹치지 않으면 다른 작품임
+    ///
+    /// 한쪽 언어만 있는 제목을 거쳐 다른 작품끼리 묶이지 않도록 묶음 단위로 봄
+    fn agree(&mut self, a: usize, b: usize, side: Side) -> bool {
+        let (a, b) = (root(&mut self.parent, a), root(&mut self.parent, b));
+        let other = match side {
+            Side::Foreign => 1,
+            Side::Korean => 0,
+        };
+        let (x, y) = (&self.keys[a][other], &self.keys[b][other]);
+
+        x.is_empty() || y.is_empty() || !x.is_disjoint(y)
+    }
+
+    fn union(&mut self, from: usize, to: usize, reason: Reason) {
+        let (a, b) = (root(&mut self.parent, from), root(&mut self.parent, to));
+        if a == b {
+            return;
+        }
+
+        let (keep, drop) = (a.min(b), a.max(b));
+        self.parent[drop] = keep;
+        let keys = std::mem::take(&mut self.keys[drop]);
+        for (x, y) in self.keys[keep].iter_mut().zip(keys) {
+            x.extend(y);
+        }
+        self.links.push(Link { from, to, reason });
+    }
+}
+
+/// 같은 작품이 여러 번 올라온 것을 묶음
+///
+/// 한쪽 언어의 키가 같고 반대쪽 언어의 키가 어긋나지 않으며 에피소드가 같거나,
+/// 에피소드가 같고 벡터의 유사도가 `threshold` 이상이면 중복으로 봄.
+/// 묶인 이유는 대표 제목에서의 경로로 남기고, 중복이 없는 제목은 결과에 없음
+pub fn group(
+    items: &[(String, Split)],
+    vectors: &[SparseVector],
+    index: &Index,
+    threshold: f32,
+) -> Vec<Group> {
+    let mut groups = Groups::new(items);
+
+    let mut seen = HashMap::<(Side, String), Vec<usize>>::new();
+    for (i, (_, x)) in items.iter().enumerate() {
+        for side in [Side::Foreign, Side::Korean] {
+            let key = key(title(x, side));
+            if key.is_empty() {
+                continue;
+            }
+
+            let same = seen.entry((side, key)).or_default();
+            for &j in same.iter() {
+                if episodes(&items[j].1) == episodes(x) && groups.agree(j, i, side) {
+                    groups.union(j, i, Reason::Key(side));
+                }
+            }
+            same.push(i);
+        }
+    }
+
+    for (i, vector) in vectors.iter().enumerate() {
+        for (j, score) in index.search(vector, NEIGHBORS) {
+            if i == j || score < threshold || episodes(&items[i].1) != episodes(&items[j].1) {
+                continue;
+            }
+
+            groups.union(i, j, Reason::Similar(score));
+        }
+    }
+
+    let Groups {
+        mut parent, links, ..
+    } = groups;
+    let mut members = HashMap::<usize, (Vec<usize>, Vec<Link>)>::new();
+    for i in 0..items.len() {
+        members.entry(root(&mut parent, i)).or_default().0.push(i);
+    }
+    for link in links {
+        members
+            .entry(root(&mut parent, link.from))
+            .or_default()
+            .1
+            .push(link);
+    }
+
+    let mut res = members
+        .into_values()
+        .filter(|(xs, _)| xs.len() > 1)
+        .map(|(xs, links)| {
+            let c = canonical(items, &xs);
+            let mut paths = paths(c, &links);
+
+            Group {
+                canonical: c,
+                title: items[c].0.clone(),
+                duplicates: xs
+                    .into_iter()
+                    .filter(|i| *i != c)
+                    .map(|i| Duplicate {
+                        index: i,
+                        title: items[i].0.clone(),
+                        links: paths.remove(&i).unwrap_or_default(),
+                        differences: differences(&items[c].1, &items[i].1),
+                    })
+                    .collect(),
+            }
+        })
+        .collect::<Vec<_>>();
+
+    // 먼저 나온 묶음부터
+    res.sort_by_key(|x| {
+        x.duplicates
+            .iter()
+            .map(|x| x.index)
+            .chain([x.canonical])
+            .min()
+    });
+
+    res
+}
+
+fn difference_to_json(x: &Difference) -> Value {
+    match x {
+        Difference::Spelling => json!({ "kind": "spelling" }),
+        Difference::Tags(tags) => json!({ "kind": "tags", "texts": tags }),
+        Difference::Credit => json!({ "kind": "credit" }),
+        Difference::Parody => json!({ "kind": "parody" }),
+        Difference::Trimmed(texts) => json!({ "kind": "trimmed", "texts": texts }),
+        Difference::MissingSide(side) => json!({ "kind": "missing", "side": side_name(*side) }),
+        Difference::Title(side) => json!({ "kind": "title", "side": side_name(*side) }),
+    }
+}
+
+fn side_name(side: Side) -> &'static str {
+    match side {
+        Side::Foreign => "foreign",
+        Side::Korean => "korean",
+    }
+}
+
+fn link_to_json(x: &Link) -> Value {
+    let reason = match x.reason {
+        Reason::Key(side) => json!({ "kind": "key", "side": side_name(side) }),
+        Reason::Similar(score) => json!({ "kind": "similar", "score": score }),
+    };
+
+    json!({ "from": x.from, "to": x.to, "reason": reason })
+}
+
+pub fn to_json(groups: &[Group]) -> Value {
+    groups
+        .iter()
+        .map(|x| {
+            json!({
+                "canonical": { "index": x.canonical, "title": x.title },
+                "duplicates": x.duplicates.iter().map(|d| json!({
+                    "index": d.index,
+                    "title": d.title,
+                    "matched": d.links.iter().map(link_to_json).collect::<Vec<_>>(),
+                    "ignored": d.differences.iter().map(difference_to_json).collect::<Vec<_>>(),
+                })).collect::<Vec<_>>(),
+            })
+        })
+        .collect()
+}
+
+#[test]
+fn test_group() -> Result<(), Box<dyn std::error::Error>> {
+    use rkiwi::KiwiBuilder;
+
+    use crate::tfidf::Vectorizer;
+
+    let kiwi = KiwiBuilder::new(None, Default::default())?.build(None, None)?;
+
+    let items = [
+        "[Korean] Kanojo no Himitsu 2",
+        "Kanojo no Himitsu 2 | 여자친구 비밀 2",
+        "Kanojo  no HIMITSU 2 (Decensored)",
+        "Kanojo no Himitsu 3",
+        "(C97) [Circle (Artist)] Kanojo no Himitsu 2",
+        "Natsuyasumi no Bus Tour",
+        "Natsuyasumi no Basu Tour",
+        "Himitsu no Kanojo 2 | 여자친구 비밀 2",
+        "Himitsu no Kanojo 2",
+        "여자친구 비밀 2",
+    ]
+    .into_iter()
+    .map(|x| Ok((x.to_owned(), crate::process(&kiwi, x)?)))
+    .collect::<Result<Vec<_>, Box<dyn std::error::Error>>>()?;
+
+    // 키만으로 묶음
+    let res = group(&items, &[], &Index::new(0), 0.8);
+    assert_eq!(res.len(), 2);
+    assert_eq!(res[0].canonical, 1);
+
+    // 한국어 키가 같아도 외국어 제목이 다르면 다른 작품임
+    let duplicates = &res[0].duplicates;
+    assert_eq!(
+        duplicates.iter().map(|x| x.index).collect::<Vec<_>>(),
+        [0, 2, 4, 9]
+    );
+    assert_eq!(
+        duplicates[0].links,
+        [Link {
+            from: 1,
+            to: 0,
+            reason: Reason::Key(Side::Foreign)
+        }]
+    );
+    assert_eq!(
+        duplicates[0].differences,
+        [
+            Difference::MissingSide(Side::Korean),
+            Difference::Tags(vec!["[korean]".to_owned()])
+        ]
+    );
+    assert_eq!(
+        duplicates[1].differences,
+        [
+            Difference::MissingSide(Side::Korean),
+            Difference::Spelling,
+            Difference::Tags(vec!["(decensored)".to_owned()])
+        ]
+    );
+    assert!(duplicates[2].differences.contains(&Difference::Credit));
+
+    // 대표 제목과 직접 같지 않아도 묶인 경로를 그대로 보여줌
+    let reasons = |x: &Duplicate| x.links.iter().map(|x| x.reason).collect::<Vec<_>>();
+    assert_eq!(
+        reasons(&duplicates[2]),
+        [Reason::Key(Side::Foreign), Reason::Key(Side::Foreign)]
+    );
+    assert_eq!(reasons(&duplicates[3]), [Reason::Key(Side::Korean)]);
+    assert_eq!(
+        duplicates[3].differences,
+        [Difference::MissingSide(Side::Foreign)]
+    );
+
+    // 한국어만 있는 제목을 거쳐 다른 작품과 묶이지 않음
+    assert_eq!(res[1].canonical, 7);
+    assert_eq!(
+        res[1]
+            .duplicates
+            .iter()
+            .map(|x| x.index)
+            .collect::<Vec<_>>(),
+        [8]
+    );
+
+    // 벡터가 비슷한 것도 묶음. 어순만 다른 제목은 벡터로 가릴 수 없으므로 뺌
+    let items = &items[..7];
+    let vectorizer = Vectorizer::fit(&kiwi, items)?;
+    let vectors = items
+        .iter()
+        .map(|(_, x)| vectorizer.transform(&kiwi, x))
+        .collect::<Result<Vec<_>, _>>()?;
+    let mut index = Index::new(vectorizer.len());
+    for (i, x) in vectors.iter().enumerate() {
+        index.add(i, x.clone());
+    }
+
+    let res = group(items, &vectors, &index, 0.6);
+    assert_eq!(res.len(), 2);
+    assert_eq!(res[1].canonical, 5);
+    assert_eq!(res[1].duplicates[0].index, 6);
+    assert!(matches!(
+        reasons(&res[1].duplicates[0])[..],
+        [Reason::Similar(x)] if x >= 0.6
+    ));
+    assert_eq!(
+        res[1].duplicates[0].differences,
+        [Difference::Title(Side::Foreign)]
+    );
+
+    // 에피소드가 다르면 키가 같아도 묶지 않음
+    let items = [
+        "Kanojo no Himitsu 2",
+        "Kanojo no Himitsu (2)",
+        "Kanojo no Himitsu2",
+    ]
+    .into_iter()
+    .map(|x| Ok((x.to_owned(), crate::process(&kiwi, x)?)))
+    .collect::<Result<Vec<_>, Box<dyn std::error::Error>>>()?;
+    let res = group(&items, &[], &Index::new(0), 0.8);
+    assert_eq!(res.len(), 1);
+    assert_eq!(
+        res[0]
+            .duplicates
+            .iter()
+            .map(|x| x.index)
+            .collect::<Vec<_>>(),
+        [1]
+    );
+
+    Ok(())
+}
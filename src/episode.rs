@@ -0,0 +1,566 @@
+use std::ops::{Range, RangeInclusive};
+
+use rkiwi::{POSTag, analyzed::Token};
+use widestring::{U16Str, U16String, u16str};
+
+use crate::{
+    is_korean, ordinal,
+    segment::{Side, end_of, glued, is_word},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum EpisodeKind {
+    /// `Vol.05` `2권`
+    Volume,
+    /// `Ch. 1` `3장`
+    Chapter,
+    /// `Part 2` `2부`
+    Part,
+    /// `Ep. 3` `제2화` `Manga 2` `Dainiwa` `#2` `No.5`
+    Episode,
+    /// `1~13` `6-7`
+    Range,
+    /// `(Zenpen)` `Kouhen` `전편` `후편` `(상)`
+    PrequelSequel,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Episode {
+    pub kind: EpisodeKind,
+    pub start: u32,
+    /// 범위가 아니면 `start`와 같음
+    pub end: u32,
+    /// `Kouhen` 처럼 전체 개수를 알 수 있는 경우
+    pub total: Option<u32>,
+    /// 원문
+    pub text: String,
+    /// 원문 위치 (utf-16)
+    pub span: Range<usize>,
+    /// 에피소드가 붙은 언어
+    pub side: Side,
+    /// 에피소드가 원래 나타난 언어
+    ///
+    /// 반대쪽 언어에서 옮겨 온 경우 `side`와 다름
+    pub origin: Side,
+}
+
+const EPISODE_WORDS: [(&str, EpisodeKind); 9] = [
+    ("vol", EpisodeKind::Volume),
+    ("volume", EpisodeKind::Volume),
+    ("ch", EpisodeKind::Chapter),
+    ("chap", EpisodeKind::Chapter),
+    ("chapter", EpisodeKind::Chapter),
+    ("part", EpisodeKind::Part),
+    ("ep", EpisodeKind::Episode),
+    ("episode", EpisodeKind::Episode),
+    ("act", EpisodeKind::Episode),
+];
+
+/// `제2화` `3권` 처럼 숫자 뒤에 붙는 단위
+const COUNTERS: [(&str, EpisodeKind); 6] = [
+    ("화", EpisodeKind::Episode),
+    ("회", EpisodeKind::Episode),
+    ("권", EpisodeKind::Volume),
+    ("장", EpisodeKind::Chapter),
+    ("부", EpisodeKind::Part),
+    ("편", EpisodeKind::Part),
+];
+
+/// `601 Goushitsu` `601호실` 처럼 방 번호 뒤에 붙는 단어
+const ROOM_SUFFIXES: [&str; 5] = ["goushitsu", "goshitsu", "gōshitsu", "号室", "호실"];
+
+/// `Room 601` 처럼 방 번호 앞에 붙는 단어
+const ROOM_PREFIXES: [&str; 2] = ["room", "rm"];
+
+/// `Kaoru (33)` 처럼 이름 뒤 괄호 안에 있을 때 나이로 보는 숫자
+const AGES: RangeInclusive<u32> = 10..=99;
+
+/// `Dai-2-wa` 처럼 여러 토큰으로 나뉜 서수를 합쳐 볼 최대 토큰 수
+const MAX_ORDINAL_TOKENS: usize = 5;
+
+const RANGE_CHARS: [&U16Str; 3] = [u16str!("~"), u16str!("～"), u16str!("-")];
+
+/// 전각 숫자도 처리함
+pub fn parse_number(x: &U16Str) -> Option<u32> {
+    let x = x
+        .to_string_lossy()
+        .chars()
+        .map(|c| match c {
+            '０'..='９' => char::from_u32(c as u32 - '０' as u32 + '0' as u32).unwrap_or(c),
+            c => c,
+        })
+        .collect::<String>();
+
+    x.parse().ok()
+}
+
+/// 토큰 위치에서 찾은 에피소드 표기
+#[derive(Debug, Clone, Copy)]
+pub struct Matched {
+    pub kind: EpisodeKind,
+    pub start: u32,
+    pub end: u32,
+    pub total: Option<u32>,
+    /// 표기가 끝난 다음 토큰 위치
+    pub next: usize,
+}
+
+const fn matched(kind: EpisodeKind, start: u32, end: u32, next: usize) -> Matched {
+    Matched {
+        kind,
+        start,
+        end,
+        total: None,
+        next,
+    }
+}
+
+fn number_at(xs: &[(U16String, Token)], i: usize) -> Option<u32> {
+    let (form, token) = xs.get(i)?;
+    (token.tag == POSTag::SN).then(|| parse_number(form))?
+}
+
+/// `1~13` 에서 `~13` 에 해당하는 부분
+fn range_end_at(xs: &[(U16String, Token)], i: usize) -> Option<(u32, usize)> {
+    let (form, token) = xs.get(i)?;
+
+    if !RANGE_CHARS.contains(&form.as_ustr()) || !glued(&xs[i - 1].1, token) {
+        return None;
+    }
+
+    let end = number_at(xs, i + 1)?;
+    glued(token, &xs[i + 1].1).then_some((end, i + 2))
+}
+
+/// 숫자 앞에 붙은 에피소드 표기 (`Vol.05` `Ch. 1` `Part 2` `Vol.1-3`)
+pub fn marker(xs: &[(U16String, Token)], i: usize) -> Option<Matched> {
+    let (form, token) = &xs[i];
+
+    if token.tag != POSTag::SL {
+        return None;
+    }
+
+    let form = form.to_string_lossy().to_lowercase();
+    let (_, kind) = EPISODE_WORDS.iter().find(|(x, _)| *x == form)?;
+
+    let mut j = i + 1;
+    if xs.get(j).is_some_and(|(f, _)| f == u16str!(".")) {
+        j += 1;
+    }
+
+    let start = number_at(xs, j)?;
+
+    Some(match range_end_at(xs, j + 1) {
+        Some((end, k)) => matched(*kind, start, end, k),
+        None => matched(*kind, start, start, j + 1),
+    })
+}
+
+/// `Harent#2` `#2-3` `No.5` `№5`
+fn number_sign(xs: &[(U16String, Token)], i: usize) -> Option<Matched> {
+    let (form, token) = &xs[i];
+    let form = form.to_string_lossy();
+
+    let glued_at = |j: usize| xs.get(j).is_some_and(|(_, t)| glued(&xs[j - 1].1, t));
+
+    let (start, j) = if token.tag == POSTag::W_HASHTAG {
+        let rest = form.strip_prefix(['#', '＃'])?;
+        (parse_number(&U16String::from_str(rest))?, i + 1)
+    } else if ["#", "＃", "№"].contains(&form.as_str()) && glued_at(i + 1) {
+        (number_at(xs, i + 1)?, i + 2)
+    } else if form.eq_ignore_ascii_case("no") && glued_at(i + 1) && xs[i + 1].0 == u16str!(".") {
+        (number_at(xs, i + 2)?, i + 3)
+    } else {
+        return None;
+    };
+
+    Some(match range_end_at(xs, j) {
+        Some((end, k)) => matched(EpisodeKind::Episode, start, end, k),
+        None => matched(EpisodeKind::Episode, start, start, j),
+    })
+}
+
+/// 표기만으로 에피소드임을 알 수 있는 것 (`Vol.05` `Dainiwa` `#2`)
+///
+/// 맨 숫자나 `2화` 처럼 앞뒤 문맥을 봐야 하는 것은 제외함
+pub fn at(xs: &[(U16String, Token)], i: usize) -> Option<Matched> {
+    marker(xs, i)
+        .or_else(|| number_sign(xs, i))
+        .or_else(|| ordinal(xs, i))
+}
+
+/// `제2화` `2화` `3권`
+fn counter(xs: &[(U16String, Token)], i: usize) -> Option<Matched> {
+    let j = if xs[i].0 == u16str!("제") && xs.get(i + 1).is_some_and(|(_, t)| glued(&xs[i].1, t)) {
+        i + 1
+    } else {
+        i
+    };
+
+    let start = number_at(xs, j)?;
+    let (end, k) = range_end_at(xs, j + 1).unwrap_or((start, j + 1));
+
+    let (form, token) = xs.get(k)?;
+    let form = form.to_string_lossy();
+    let (_, kind) = COUNTERS.iter().find(|(x, _)| *x == form)?;
+
+    glued(&xs[k - 1].1, token).then_some(matched(*kind, start, end, k + 1))
+}
+
+/// `Dainiwa` `Dai-2-wa` `Kouhen` `후편` `(상)`
+///
+/// 앞뒤로 다른 단어가 붙어 있으면 서수로 보지 않음
+pub fn ordinal(xs: &[(U16String, Token)], i: usize) -> Option<Matched> {
+    if i > 0 && glued(&xs[i - 1].1, &xs[i].1) && is_word(&xs[i - 1].1.tag) {
+        return None;
+    }
+
+    let to_matched = |x: ordinal::Ordinal, next: usize| Matched {
+        total: x.total,
+        ..matched(x.kind, x.number, x.number, next)
+    };
+
+    // 괄호 안에 홀로 있는 `상` `하`
+    let enclosed = i > 0
+        && xs[i - 1].1.tag == POSTag::SSO
+        && xs.get(i + 1).is_some_and(|(_, t)| t.tag == POSTag::SSC);
+    if enclosed {
+        let form = xs[i].0.to_string_lossy();
+        if let Some((_, x)) = ordinal::BRACKETED_PART_WORDS
+            .iter()
+            .find(|(w, _)| *w == form)
+        {
+            return Some(to_matched(*x, i + 1));
+        }
+    }
+
+    // `Dai 2 wa` 처럼 `Dai` 로 시작하면 띄어 쓴 토큰도 합쳐 봄
+    let spaced = xs[i].0.to_string_lossy().eq_ignore_ascii_case("dai");
+
+    let mut form = String::new();
+    let mut found = None;
+
+    for j in i..(i + MAX_ORDINAL_TOKENS).min(xs.len()) {
+        if j > i && !spaced && !glued(&xs[j - 1].1, &xs[j].1) {
+            break;
+        }
+
+        form.push_str(&xs[j].0.to_string_lossy());
+
+        if let Some(x) = ordinal::parse(&form) {
+            found = Some(to_matched(x, j + 1));
+        }
+    }
+
+    let found = found?;
+    let last = &xs[found.next - 1].1;
+    let glued_next = xs
+        .get(found.next)
+        .is_some_and(|(_, t)| glued(last, t) && is_word(&t.tag));
+
+    (!glued_next).then_some(found)
+}
+
+/// `i` 부터 붙어 있는 토큰을 최대 `n`개까지 이어 붙인 소문자 문자열들
+fn glued_forms(xs: &[(U16String, Token)], i: usize, n: usize) -> Vec<(String, usize)> {
+    let mut res = Vec::new();
+    let mut form = String::new();
+
+    for j in i..(i + n).min(xs.len()) {
+        if j > i && !glued(&xs[j - 1].1, &xs[j].1) {
+            break;
+        }
+
+        form.push_str(&xs[j].0.to_string_lossy().to_lowercase());
+        res.push((form.clone(), j + 1));
+    }
+
+    res
+}
+
+/// 에피소드가 아닌 숫자 (`601 Goushitsu` `601호실` `Room 601` `Kaoru (33)`)
+///
+/// 건너뛸 다음 토큰 위치를 반환함
+fn not_episode(xs: &[(U16String, Token)], i: usize) -> Option<usize> {
+    let room_prefix = glued_forms(xs, i, 2)
+        .into_iter()
+        .find(|(x, _)| ROOM_PREFIXES.contains(&x.as_str()));
+    if let Some((_, j)) = room_prefix {
+        return number_at(xs, j).map(|_| j + 1);
+    }
+
+    let n = number_at(xs, i)?;
+
+    let room_suffix = glued_forms(xs, i + 1, 3)
+        .into_iter()
+        .find(|(x, _)| ROOM_SUFFIXES.contains(&x.as_str()));
+    if let Some((_, next)) = room_suffix {
+        return Some(next);
+    }
+
+    // 이름 바로 뒤 괄호 안에 홀로 있는 숫자
+    (i > 1 && enclosed(xs, i) && is_name(xs, i - 2) && AGES.contains(&n)).then_some(i + 2)
+}
+
+/// 괄호 안에 홀로 있는 토큰인지 (`(12)`)
+fn enclosed(xs: &[(U16String, Token)], i: usize) -> bool {
+    i > 0
+        && xs[i - 1].1.tag == POSTag::SSO
+        && xs.get(i + 1).is_some_and(|(_, t)| t.tag == POSTag::SSC)
+}
+
+/// 대문자로 시작하는 로마자 단어
+fn is_capitalized(x: &(U16String, Token)) -> bool {
+    x.1.tag == POSTag::SL && x.0.to_string_lossy().starts_with(char::is_uppercase)
+}
+
+/// `i`에서 끝나는 이름인지 (`소노자키 카오루` `Sonosaki Kaoru`)
+///
+/// 로마자는 제목 뒤에 성과 이름이 대문자로 시작하는 두 단어로 있을 때만 이름으로 봄
+fn is_name(xs: &[(U16String, Token)], i: usize) -> bool {
+    if xs[i].1.tag == POSTag::NNP {
+        return true;
+    }
+
+    i >= 2 && is_capitalized(&xs[i]) && is_capitalized(&xs[i - 1]) && !glued(&xs[i - 1].1, &xs[i].1)
+}
+
+/// 공백으로 떨어진 `1~13` `6-7`
+fn range(xs: &[(U16String, Token)], i: usize) -> Option<Matched> {
+    if i > 0 && glued(&xs[i - 1].1, &xs[i].1) {
+        return None;
+    }
+
+    let start = number_at(xs, i)?;
+    let (end, k) = range_end_at(xs, i + 1)?;
+
+    let glued_next = xs.get(k).is_some_and(|(_, t)| glued(&xs[k - 1].1, t));
+    (!glued_next).then_some(matched(EpisodeKind::Range, start, end, k))
+}
+
+/// 제목 끝에 공백으로 떨어져 있는 숫자 (`Ero Manga 2`, `변태여친 1`)
+///
+/// 뒤에는 괄호나 기호만 올 수 있고, 괄호 안에 홀로 있어도 됨 (`Kanojo no Himitsu (12)`).
+/// 뒤에 오는 괄호 안의 단어는 무시함 (`Kanojo no Himitsu 2 (Decensored)`)
+fn trailing_number(xs: &[(U16String, Token)], tokens: &Range<usize>, i: usize) -> Option<Matched> {
+    if i == 0 || (glued(&xs[i - 1].1, &xs[i].1) && !enclosed(xs, i)) {
+        return None;
+    }
+
+    let n = number_at(xs, i)?;
+
+    let glued_next = xs.get(i + 1).is_some_and(|(_, t)| {
+        glued(&xs[i].1, t) && (t.tag == POSTag::SN || t.tag == POSTag::SL || is_korean(&t.tag))
+    });
+    // 뒤에 붙은 `(Decensored)` 같은 괄호 안의 단어는 제목이 이어지는 것이 아님
+    let mut depth = 0usize;
+    let followed_by_word = xs[i + 1..tokens.end].iter().any(|(_, t)| {
+        match t.tag {
+            POSTag::SSO => depth += 1,
+            POSTag::SSC => depth = depth.saturating_sub(1),
+            _ => {}
+        }
+
+        depth == 0 && (t.tag == POSTag::SL || t.tag == POSTag::SH || is_korean(&t.tag))
+    });
+
+    (!glued_next && !followed_by_word).then_some(matched(EpisodeKind::Episode, n, n, i + 1))
+}
+
+/// 한 언어 구간에서 에피소드 표기를 찾음
+///
+/// `span`은 원문 위치 (utf-16)
+pub fn extract(
+    text: &U16Str,
+    xs: &[(U16String, Token)],
+    span: Range<usize>,
+    side: Side,
+) -> Vec<Episode> {
+    let tokens = {
+        let mut within = xs
+            .iter()
+            .enumerate()
+            .filter(|(_, (_, t))| span.start <= t.chr_position && end_of(t) <= span.end)
+            .map(|(i, _)| i);
+
+        match (within.next(), within.next_back()) {
+            (Some(start), Some(end)) => start..end + 1,
+            (Some(start), None) => start..start + 1,
+            _ => return Vec::new(),
+        }
+    };
+
+    let mut res = Vec::new();
+    let mut i = tokens.start;
+
+    while i < tokens.end {
+        if let Some(next) = not_episode(xs, i) {
+            i = next;
+            continue;
+        }
+
+        let found = marker(xs, i)
+            .or_else(|| number_sign(xs, i))
+            .or_else(|| counter(xs, i))
+            .or_else(|| ordinal(xs, i))
+            .or_else(|| range(xs, i))
+            .or_else(|| trailing_number(xs, &tokens, i))
+            .filter(|x| x.next <= tokens.end);
+
+        match found {
+            Some(Matched {
+                kind,
+                start,
+                end,
+                total,
+                next,
+            }) => {
+                let span = xs[i].1.chr_position..end_of(&xs[next - 1].1);
+
+                res.push(Episode {
+                    kind,
+                    start,
+                    end,
+                    total,
+                    text: text[span.clone()].to_string_lossy(),
+                    span,
+                    side,
+                    origin: side,
+                });
+
+                i = next;
+            }
+            None => i += 1,
+        }
+    }
+
+    res
+}
+
+/// 한쪽 언어에만 있는 에피소드를 반대쪽 언어로 옮김
+///
+/// 양쪽 모두 에피소드가 있으면 아무것도 옮기지 않음.
+/// 옮겨진 에피소드는 원문 위치와 원문을 그대로 가짐
+pub fn propagate(episodes: &[Episode]) -> Vec<Episode> {
+    let has = |side: Side| episodes.iter().any(|x| x.side == side);
+
+    [(Side::Foreign, Side::Korean), (Side::Korean, Side::Foreign)]
+        .into_iter()
+        .filter(|(from, to)| has(*from) && !has(*to))
+        .flat_map(|(from, to)| {
+            episodes
+                .iter()
+                .filter(move |x| x.side == from)
+                .map(move |x| Episode {
+                    side: to,
+                    ..x.clone()
+                })
+        })
+        .collect()
+}
+
+#[test]
+fn test_extract() -> Result<(), Box<dyn std::error::Error>> {
+    use rkiwi::{KiwiBuilder, Match};
+
+    let kiwi = KiwiBuilder::new(None, Default::default())?.build(None, None)?;
+    let match_options = Match::new().all_with_normailize_coda();
+
+    let extract = |txt: &str| -> Result<Vec<Episode>, Box<dyn std::error::Error>> {
+        let txt = U16String::from_str(txt);
+        let xs = kiwi
+            .analyze_w(&txt, 1, match_options, None, None)?
+            .to_vec_w();
+        Ok(extract(&txt, &xs, 0..txt.len(), Side::Foreign))
+    };
+
+    let res = extract("Shaving Archive -Sukitoru Yona Sekaikan Nanoni Vol.05-")?;
+    assert_eq!(res.len(), 1);
+    assert_eq!(res[0].kind, EpisodeKind::Volume);
+    assert_eq!((res[0].start, res[0].end), (5, 5));
+    assert_eq!(res[0].text, "Vol.05");
+
+    let res = extract("Hamechichi! Ch. 1 (uncensored)")?;
+    assert_eq!(res.len(), 1);
+    assert_eq!(res[0].kind, EpisodeKind::Chapter);
+    assert_eq!(res[0].text, "Ch. 1");
+
+    let res = extract("미유 쨩이 선생님의 육단지 오나펫이 되는 이야기 1~13")?;
+    assert_eq!(res.len(), 1);
+    assert_eq!(res[0].kind, EpisodeKind::Range);
+    assert_eq!((res[0].start, res[0].end), (1, 13));
+
+    let res = extract("있을 곳이 없어 카미마치 해본 버려진 소년의 에로망가 제2화")?;
+    assert_eq!(res.len(), 1);
+    assert_eq!(res[0].kind, EpisodeKind::Episode);
+    assert_eq!(res[0].start, 2);
+    assert_eq!(res[0].text, "제2화");
+
+    let res = extract("Zemi no Bounenkai (Zenpen)")?;
+    assert_eq!(res.len(), 1);
+    assert_eq!(res[0].kind, EpisodeKind::PrequelSequel);
+    assert_eq!(res[0].start, 1);
+    assert_eq!(res[0].total, Some(2));
+
+    let res = extract("Seiso Kanojo Kouhen")?;
+    assert_eq!(res.len(), 1);
+    assert_eq!(res[0].kind, EpisodeKind::PrequelSequel);
+    assert_eq!((res[0].start, res[0].total), (2, Some(2)));
+
+    let res = extract("Onee-san to Issho Dainiwa")?;
+    assert_eq!(res.len(), 1);
+    assert_eq!(res[0].kind, EpisodeKind::Episode);
+    assert_eq!((res[0].start, res[0].total), (2, None));
+    assert_eq!(res[0].text, "Dainiwa");
+
+    let res = extract("하숙집 누나 (하)")?;
+    assert_eq!(res.len(), 1);
+    assert_eq!(res[0].kind, EpisodeKind::PrequelSequel);
+    assert_eq!(res[0].start, 2);
+
+    assert!(extract("Daisuki na Onee-san")?.is_empty());
+
+    // `#N` `No.N`
+    let res = extract("Miru Harumin! To Harent#2")?;
+    assert_eq!(res.len(), 1);
+    assert_eq!(res[0].kind, EpisodeKind::Episode);
+    assert_eq!((res[0].start, res[0].text.as_str()), (2, "#2"));
+
+    let res = extract("Ochiru Hitozuma #3-4")?;
+    assert_eq!((res[0].start, res[0].end), (3, 4));
+
+    let res = extract("Kanojo Collection No.5")?;
+    assert_eq!((res[0].start, res[0].text.as_str()), (5, "No.5"));
+
+    assert!(extract("No 5 ni Naritai")?.is_empty());
+
+    // 방 번호, 나이
+    let res = extract("Mama Mansion! Dainiwa 601 Goushitsu Sonosaki Kaoru (33)")?;
+    assert_eq!(res.len(), 1);
+    assert_eq!(res[0].text, "Dainiwa");
+
+    let res = extract("마마 맨션! 제2화 601호실 소노자키 카오루")?;
+    assert_eq!(res.len(), 1);
+    assert_eq!(res[0].text, "제2화");
+
+    assert!(extract("Kanrinin-san Room 601")?.is_empty());
+
+    // 이름 뒤가 아니면 괄호 안의 숫자도 에피소드임
+    let res = extract("Kanojo no Himitsu (12)")?;
+    assert_eq!(res.len(), 1);
+    assert_eq!(res[0].start, 12);
+
+    let res = extract("Kanojo no Himitsu 2 (Decensored)")?;
+    assert_eq!(res.len(), 1);
+    assert_eq!(res[0].start, 2);
+
+    let res = extract("Ibasho ga Nai node Kamimachi shite mita Suterareta Shounen no Ero Manga 2")?;
+    assert_eq!(res.len(), 1);
+    assert_eq!(res[0].kind, EpisodeKind::Episode);
+    assert_eq!(res[0].start, 2);
+
+    assert!(extract("Patreon 2019/02~2025/02 Tier2 Reward")?.is_empty());
+    assert!(extract("철혈 M16")?.is_empty());
+
+    Ok(())
+}
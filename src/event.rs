@@ -0,0 +1,210 @@
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Season {
+    Spring,
+    Summer,
+    Autumn,
+    Winter,
+}
+
+const SEASONS: [(&str, Season); 9] = [
+    ("spring", Season::Spring),
+    ("summer", Season::Summer),
+    ("autumn", Season::Autumn),
+    ("fall", Season::Autumn),
+    ("winter", Season::Winter),
+    ("春", Season::Spring),
+    ("夏", Season::Summer),
+    ("秋", Season::Autumn),
+    ("冬", Season::Winter),
+];
+
+/// 4자리 숫자가 이 범위에 있으면 회차가 아니라 연도로 봄
+const YEARS: std::ops::RangeInclusive<u32> = 1975..=2100;
+
+/// `(C97)` `(SC2019 Summer)` 같은 행사
+///
+/// 이름, 연도, 회차, 계절 순으로 정렬됨
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Event {
+    /// 알려진 행사면 대표 이름, 아니면 괄호 안쪽 원문
+    pub name: String,
+    pub year: Option<u32>,
+    pub number: Option<u32>,
+    pub season: Option<Season>,
+    /// 괄호 안쪽 원문
+    pub text: String,
+}
+
+impl Event {
+    /// 알려진 행사가 아닐 때
+    pub fn unknown(text: &str) -> Self {
+        Self {
+            name: text.to_owned(),
+            year: None,
+            number: None,
+            season: None,
+            text: text.to_owned(),
+        }
+    }
+}
+
+/// 행사 표기 앞부분과 대표 이름
+///
+/// `add`로 표기를 추가할 수 있음
+#[derive(Debug, Clone)]
+pub struct Events {
+    /// 긴 표기부터 정렬됨
+    patterns: Vec<(String, String)>,
+}
+
+const EVENTS: [(&str, &str); 20] = [
+    ("C", "Comic Market"),
+    ("Comic Market", "Comic Market"),
+    ("Comiket", "Comic Market"),
+    ("コミケ", "Comic Market"),
+    ("COMIC1☆", "COMIC1"),
+    ("COMIC1", "COMIC1"),
+    ("Reitaisai", "Reitaisai"),
+    ("例大祭", "Reitaisai"),
+    ("Shuuki Reitaisai", "Shuuki Reitaisai"),
+    ("秋季例大祭", "Shuuki Reitaisai"),
+    ("SC", "Sunshine Creation"),
+    ("サンクリ", "Sunshine Creation"),
+    ("Kouroumu", "Kouroumu"),
+    ("紅楼夢", "Kouroumu"),
+    ("COMITIA", "COMITIA"),
+    ("Gataket", "Gataket"),
+    ("ComiComi", "ComiComi"),
+    ("Puniket", "Puniket"),
+    ("Bokura no Love Live!", "Bokura no Love Live!"),
+    ("코믹월드", "Comic World"),
+];
+
+impl Default for Events {
+    fn default() -> Self {
+        let mut res = Self {
+            patterns: Vec::new(),
+        };
+
+        for (prefix, name) in EVENTS {
+            res.add(prefix, name);
+        }
+
+        res
+    }
+}
+
+impl Events {
+    pub fn add(&mut self, prefix: &str, name: &str) -> &mut Self {
+        self.patterns.push((prefix.to_lowercase(), name.to_owned()));
+        self.patterns
+            .sort_by_key(|(prefix, _)| std::cmp::Reverse(prefix.chars().count()));
+
+        self
+    }
+
+    /// 괄호 안쪽 문자열을 행사로 바꿈
+    ///
+    /// 표기 뒤에는 회차, 연도, 계절만 올 수 있음
+    pub fn parse(&self, x: &str) -> Option<Event> {
+        let text = x.trim();
+        let lowercase = text.to_lowercase();
+
+        self.patterns.iter().find_map(|(prefix, name)| {
+            let rest = lowercase.strip_prefix(prefix.as_str())?;
+            let mut event = Event {
+                name: name.clone(),
+                ..Event::unknown(text)
+            };
+
+            let pieces = pieces(rest);
+            if pieces.is_empty() {
+                return None;
+            }
+
+            for piece in pieces {
+                if let Ok(n) = piece.parse::<u32>() {
+                    let slot = if piece.len() == 4 && YEARS.contains(&n) {
+                        &mut event.year
+                    } else {
+                        &mut event.number
+                    };
+
+                    if slot.replace(n).is_some() {
+                        return None;
+                    }
+                } else {
+                    let (_, season) = SEASONS.iter().find(|(x, _)| *x == piece)?;
+                    event.season = Some(*season);
+                }
+            }
+
+            Some(event)
+        })
+    }
+}
+
+/// `2019 summer` `2019summer` 를 `2019` `summer` 로 나눔
+fn pieces(x: &str) -> Vec<String> {
+    let mut res: Vec<String> = Vec::new();
+    let mut prev = None;
+
+    for c in x.chars() {
+        if c.is_whitespace() || c == '-' {
+            prev = None;
+            continue;
+        }
+
+        let digit = c.is_ascii_digit();
+        match res.last_mut() {
+            Some(last) if prev == Some(digit) => last.push(c),
+            _ => res.push(c.to_string()),
+        }
+        prev = Some(digit);
+    }
+
+    res
+}
+
+#[test]
+fn test_parse() {
+    let events = Events::default();
+    let parse = |x| {
+        events
+            .parse(x)
+            .map(|x| (x.name, x.number, x.year, x.season))
+    };
+
+    assert_eq!(
+        parse("C97"),
+        Some(("Comic Market".to_owned(), Some(97), None, None))
+    );
+    assert_eq!(
+        parse("COMIC1☆15"),
+        Some(("COMIC1".to_owned(), Some(15), None, None))
+    );
+    assert_eq!(
+        parse("Reitaisai 20"),
+        Some(("Reitaisai".to_owned(), Some(20), None, None))
+    );
+    assert_eq!(
+        parse("SC2019 Summer"),
+        Some((
+            "Sunshine Creation".to_owned(),
+            None,
+            Some(2019),
+            Some(Season::Summer)
+        ))
+    );
+
+    assert_eq!(parse("Circle"), None);
+    assert_eq!(parse("C"), None);
+    assert_eq!(parse("Blue Archive"), None);
+
+    let mut events = Events::default();
+    events.add("Tora no Ana Fes", "Tora no Ana Fes");
+    assert_eq!(
+        events.parse("Tora no Ana Fes 3").and_then(|x| x.number),
+        Some(3)
+    );
+}
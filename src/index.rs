@@ -0,0 +1,262 @@
+use std::collections::BTreeMap;
+
+use serde_json::{Value, json};
+
+use crate::tfidf::SparseVector;
+
+/// k-평균을 반복하는 횟수
+const ITERATIONS: usize = 10;
+
+/// 이보다 벡터가 적으면 전부 비교해도 충분히 빠름
+const MIN_TRAIN_VECTORS: usize = 1000;
+/// 검색할 때 전체 목록 중 보는 비율의 역수
+const PROBE_RATIO: usize = 8;
+
+/// 벡터를 가장 가까운 중심의 목록에 나눠 담아, 검색할 때 가까운 목록 몇 개만 봄
+#[derive(Debug, Clone, PartialEq)]
+struct Ivf {
+    centroids: Vec<SparseVector>,
+    /// 중심마다 속한 벡터의 id
+    lists: Vec<Vec<usize>>,
+    /// 검색할 때 보는 목록 수
+    probes: usize,
+}
+
+impl Ivf {
+    /// 가까운 순으로 정렬된 중심의 번호
+    fn nearest(&self, x: &SparseVector) -> Vec<usize> {
+        let mut res = (0..self.centroids.len()).collect::<Vec<_>>();
+        let scores = self.centroids.iter().map(|c| c.dot(x)).collect::<Vec<_>>();
+        res.sort_by(|a, b| scores[*b].total_cmp(&scores[*a]).then(a.cmp(b)));
+
+        res
+    }
+
+    fn assign(&mut self, id: usize, x: &SparseVector) {
+        if let Some(&i) = self.nearest(x).first() {
+            self.lists[i].push(id);
+        }
+    }
+
+    fn unassign(&mut self, id: usize) {
+        for list in &mut self.lists {
+            list.retain(|x| *x != id);
+        }
+    }
+}
+
+/// 길이가 1인 벡터의 코사인 유사도로 가까운 벡터를 찾는 색인
+///
+/// 처음에는 모든 벡터를 비교하고, `train` 뒤에는 가까운 목록의 벡터만 비교함
+#[derive(Debug, Clone, PartialEq)]
+pub struct Index {
+    dimension: usize,
+    vectors: BTreeMap<usize, SparseVector>,
+    ivf: Option<Ivf>,
+}
+
+impl Index {
+    pub fn new(dimension: usize) -> Self {
+        Self {
+            dimension,
+            vectors: BTreeMap::new(),
+            ivf: None,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.vectors.len()
+    }
+
+    /// 같은 id의 벡터가 있으면 바꾸고 이전 벡터를 돌려줌
+    pub fn add(&mut self, id: usize, vector: SparseVector) -> Option<SparseVector> {
+        let prev = self.remove(id);
+
+        if let Some(ivf) = &mut self.ivf {
+            ivf.assign(id, &vector);
+        }
+        self.vectors.insert(id, vector);
+
+        prev
+    }
+
+    pub fn remove(&mut self, id: usize) -> Option<SparseVector> {
+        let prev = self.vectors.remove(&id)?;
+
+        if let Some(ivf) = &mut self.ivf {
+            ivf.unassign(id);
+        }
+
+        Some(prev)
+    }
+
+    /// 지금 있는 벡터로 `lists`개의 중심을 구하고, 검색할 때 `probes`개의 목록만 봄
+    ///
+    /// 중심은 id 순으로 고르게 뽑은 벡터에서 시작함
+    pub fn train(&mut self, lists: usize, probes: usize) {
+        let lists = lists.min(self.vectors.len());
+        if lists == 0 {
+            self.ivf = None;
+            return;
+        }
+
+        let step = self.vectors.len() / lists;
+        let mut ivf = Ivf {
+            centroids: self
+                .vectors
+                .values()
+                .step_by(step)
+                .take(lists)
+                .cloned()
+                .collect(),
+            lists: Vec::new(),
+            probes: probes.clamp(1, lists),
+        };
+
+        for _ in 0..ITERATIONS {
+            ivf.lists = vec![Vec::new(); lists];
+            for (id, x) in &self.vectors {
+                ivf.assign(*id, x);
+            }
+
+            // 비어 있는 목록의 중심은 그대로 둠
+            for (centroid, list) in ivf.centroids.iter_mut().zip(&ivf.lists) {
+                if list.is_empty() {
+                    continue;
+                }
+
+                let mut sum = vec![0.0; self.dimension];
+                for x in list.iter().map(|id| &self.vectors[id]) {
+                    for (i, v) in x.indices.iter().zip(&x.values) {
+                        sum[*i] += v;
+                    }
+                }
+                *centroid = SparseVector::from_dense(&sum);
+            }
+        }
+
+        ivf.lists = vec![Vec::new(); lists];
+        for (id, x) in &self.vectors {
+            ivf.assign(*id, x);
+        }
+
+        self.ivf = Some(ivf);
+    }
+
+    /// 벡터가 충분히 많으면 √n개의 목록으로 나누고, 검색할 때 그 중 `1 / PROBE_RATIO`만 봄
+    ///
+    /// 가까운 벡터를 놓칠 수 있으므로 모두 찾아야 할 때는 쓰지 않음
+    pub fn train_if_large(&mut self) {
+        if self.len() < MIN_TRAIN_VECTORS {
+            return;
+        }
+
+        let lists = (self.len() as f32).sqrt() as usize;
+        self.train(lists, lists / PROBE_RATIO);
+    }
+
+    /// 가장 가까운 `k`개의 (id, 유사도), 유사도가 높은 순
+    pub fn search(&self, query: &SparseVector, k: usize) -> Vec<(usize, f32)> {
+        let candidates: Box<dyn Iterator<Item = usize>> = match &self.ivf {
+            Some(ivf) => Box::new(
+                ivf.nearest(query)
+                    .into_iter()
+                    .take(ivf.probes)
+                    .flat_map(|i| ivf.lists[i].iter().copied()),
+            ),
+            None => Box::new(self.vectors.keys().copied()),
+        };
+
+        let mut res = candidates
+            .map(|id| (id, self.vectors[&id].dot(query)))
+            .collect::<Vec<_>>();
+        res.sort_by(|a, b| b.1.total_cmp(&a.1).then(a.0.cmp(&b.0)));
+        res.truncate(k);
+
+        res
+    }
+
+    pub fn to_json(&self) -> Value {
+        json!({
+            "dimension": self.dimension,
+            "vectors": self
+                .vectors
+                .iter()
+                .map(|(id, x)| json!({ "id": id, "vector": x.to_json() }))
+                .collect::<Vec<_>>(),
+            "ivf": self.ivf.as_ref().map(|ivf| json!({
+                "probes": ivf.probes,
+                "centroids": ivf.centroids.iter().map(SparseVector::to_json).collect::<Vec<_>>(),
+            })),
+        })
+    }
+
+    /// `to_json`으로 만든 값을 읽음, 목록은 중심으로부터 다시 만듦
+    pub fn from_json(value: &Value) -> Option<Self> {
+        let mut res = Self::new(value.get("dimension")?.as_u64()? as usize);
+
+        for x in value.get("vectors")?.as_array()? {
+            let id = x.get("id")?.as_u64()? as usize;
+            res.vectors
+                .insert(id, SparseVector::from_json(x.get("vector")?)?);
+        }
+
+        if let Some(ivf) = value.get("ivf").filter(|x| !x.is_null()) {
+            let centroids = ivf
+                .get("centroids")?
+                .as_array()?
+                .iter()
+                .map(SparseVector::from_json)
+                .collect::<Option<Vec<_>>>()?;
+
+            let mut ivf = Ivf {
+                lists: vec![Vec::new(); centroids.len()],
+                centroids,
+                probes: ivf.get("probes")?.as_u64()? as usize,
+            };
+            for (id, x) in &res.vectors {
+                ivf.assign(*id, x);
+            }
+            res.ivf = Some(ivf);
+        }
+
+        Some(res)
+    }
+}
+
+#[test]
+fn test_search() {
+    let v = |x: &[f32]| SparseVector::from_dense(x);
+
+    let mut index = Index::new(3);
+    index.add(0, v(&[1.0, 0.0, 0.0]));
+    index.add(1, v(&[0.9, 0.1, 0.0]));
+    index.add(2, v(&[0.0, 1.0, 0.0]));
+    index.add(3, v(&[0.0, 0.9, 0.1]));
+    index.add(4, v(&[0.0, 0.0, 1.0]));
+
+    let ids = |xs: Vec<(usize, f32)>| xs.into_iter().map(|(id, _)| id).collect::<Vec<_>>();
+
+    let query = v(&[1.0, 0.2, 0.0]);
+    assert_eq!(ids(index.search(&query, 2)), [1, 0]);
+
+    assert!(index.remove(1).is_some());
+    assert!(index.remove(1).is_none());
+    assert_eq!(ids(index.search(&query, 2)), [0, 2]);
+
+    // 목록을 모두 보면 전부 비교한 것과 같음
+    let flat = index.search(&query, 5);
+    index.train(2, 2);
+    assert_eq!(index.search(&query, 5), flat);
+
+    // 가까운 목록만 봄
+    index.train(2, 1);
+    assert_eq!(ids(index.search(&v(&[0.0, 0.0, 1.0]), 5)), [4, 3, 2]);
+
+    index.add(5, v(&[0.1, 0.0, 1.0]));
+    assert_eq!(ids(index.search(&v(&[0.0, 0.0, 1.0]), 1)), [4]);
+    assert_eq!(index.len(), 5);
+
+    let loaded = Index::from_json(&index.to_json());
+    assert_eq!(loaded.as_ref(), Some(&index));
+}
@@ -1,205 +1,305 @@
 #![allow(clippy::collapsible_else_if)]
+mod align;
+mod bracket;
+mod classify;
+mod consistency;
+mod credit;
+mod dedup;
+mod episode;
+mod event;
+mod index;
+mod normalize;
+mod ordinal;
+mod parody;
+mod romaji;
+mod romanize;
+mod segment;
+mod series;
+mod tag;
+mod tfidf;
+mod trim;
+
 use std::{fs::File, io::BufReader};
 
-use arrayvec::ArrayVec;
+use bracket::Bracket;
 use byteview::ByteView;
+use classify::{Classification, EntryKind};
+use consistency::Consistency;
+use episode::Episode;
+use event::{Event, Events};
 use futures::{StreamExt, stream};
-use rkiwi::{Kiwi, KiwiBuilder, Match, POSTag, analyzed::Token};
+use parody::{Franchises, Parody};
+use rkiwi::{Kiwi, KiwiBuilder, Match, POSTag};
+use segment::{Run, Side};
+use tag::Release;
 use tokio::fs;
+use trim::{TrimOptions, Trimmed};
 use widestring::{U16Str, U16String, u16str};
 
-#[tokio::main]
-async fn main() -> Result<(), Box<dyn std::error::Error>> {
-    let kiwi = KiwiBuilder::new(None, Default::default())?.build(None, None)?;
+async fn read_titles(path: &str) -> Result<Vec<String>, Box<dyn std::error::Error>> {
+    let mut reader = BufReader::new(File::open(path)?);
 
-    let mut reader = BufReader::new(File::open("data.json")?);
+    let bytes = ByteView::from_reader(&mut reader, fs::metadata(path).await?.len() as usize)?;
 
-    let bytes =
-        ByteView::from_reader(&mut reader, fs::metadata("data.json").await?.len() as usize)?;
+    Ok(serde_json::from_slice::<Vec<String>>(&bytes)?)
+}
 
-    let xs = serde_json::from_slice::<Vec<String>>(&bytes)?;
+/// `kaleido align`으로 만든 대응표를 읽음
+async fn read_alignment(path: &str) -> Result<align::Table, Box<dyn std::error::Error>> {
+    let value = serde_json::from_slice(&fs::read(path).await?)?;
 
-    stream::iter(xs)
-        // .take()
-        .for_each_concurrent(6, |x| {
+    Ok(align::Table::from_json(&value).ok_or("invalid alignment table")?)
+}
+
+/// 이미지셋, 후원 보상은 지원하지 않음
+fn is_supported(x: &str) -> bool {
+    let classification = classify::classify(x);
+
+    if matches!(
+        classification.kind,
+        EntryKind::ImageSet | EntryKind::RewardPack
+    ) {
+        println!(
+            "skipped : {:?} {} ({})",
+            classification.kind,
+            x,
+            classification.evidence.unwrap_or_default()
+        );
+        return false;
+    }
+
+    true
+}
+
+/// 지원하는 제목을 입력 순서대로 나눔
+async fn process_all(kiwi: &Kiwi, xs: Vec<String>) -> Vec<(String, Split)> {
+    stream::iter(xs.into_iter().filter(|x| is_supported(x)))
+        .map(|x| {
             let kiwi = kiwi.clone();
             async move {
-                process(&kiwi, &x).unwrap();
+                let split = process(&kiwi, &x).unwrap();
+                (x, split)
             }
         })
-        .await;
+        .buffered(6)
+        .collect::<Vec<_>>()
+        .await
+}
 
-    // let mut index = index_factory(8, "Flat", MetricType::L2).unwrap();
+/// `kaleido [input] [alignment]` 각 제목을 나누고 두 제목이 같은 작품을 가리키는지 확인함, 대응표가 있으면 근거로 씀
+///
+/// `kaleido series [input] [output]` 제목을 시리즈로 묶고, 빠진 언어의 제목을 채워 `output`에 씀
+///
+/// `kaleido align [input] [output]` 양쪽 언어가 있는 제목으로 로마자와 한국어 형태소의 대응표를 만들어 `output`에 씀
+///
+/// `kaleido dedup [input] [output] [threshold]` 태그, 띄어쓰기, 괄호, 제작 정보만 다르거나 벡터가 `threshold` 이상 비슷한 제목을 묶어
+/// 대표 제목, 묶인 이유, 무시한 차이를 `output`에 씀
+///
+/// `kaleido romanize [text]` 한글을 국어의 로마자 표기법과 매큔-라이샤워 표기법으로 옮김
+///
+/// `kaleido similar [title] [vectorizer] [index]` `vectorize`로 만든 색인에서 비슷한 제목을 찾음
+///
+/// `kaleido suggest [input] [alignment]` 외국어만 있는 제목에 대응표로 한국어 형태소를 제안하고, 없으면 소리대로 옮김
+///
+/// `kaleido vectorize [input] [vectorizer] [index]` 제목들로 TF-IDF 단어 목록을 만들어 `vectorizer`에, 각 제목과 그 벡터를 담은 색인을 `index`에 씀.
+/// `vectorizer`가 이미 있으면 그 단어 목록을 씀
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let args = std::env::args().skip(1).collect::<Vec<_>>();
+    let arg = |i: usize, default: &'static str| args.get(i).map_or(default, String::as_str);
 
-    // index.add(x);
+    let kiwi = KiwiBuilder::new(None, Default::default())?.build(None, None)?;
 
-    Ok(())
-}
+    if args.first().is_some_and(|x| x == "series") {
+        let items = process_all(&kiwi, read_titles(arg(1, "data.json")).await?).await;
 
-fn is_korean(pos_tag: &POSTag) -> bool {
-    !(POSTag::SF..=POSTag::W_EMOJI).contains(pos_tag)
-}
+        let mut series = series::group(&items);
+        series::fill_missing(&mut series);
+        fs::write(
+            arg(2, "series.json"),
+            serde_json::to_vec_pretty(&series::to_json(&series))?,
+        )
+        .await?;
 
-fn is_special(pos_tag: &POSTag) -> bool {
-    (POSTag::SF..=POSTag::SB).contains(pos_tag) || (POSTag::SN..=POSTag::W_EMOJI).contains(pos_tag)
-}
+        return Ok(());
+    }
 
-fn find_subtitle<'a>(
-    xs: impl Iterator<Item = &'a (U16String, Token)>,
-) -> ArrayVec<(usize, usize), 2> {
-    let mut res = ArrayVec::<_, 2>::new();
-
-    let mut start = 0;
-    let mut open_so = false;
-
-    for (i, (_form, token)) in xs.enumerate() {
-        if token.tag == POSTag::SO && !open_so {
-            open_so = true;
-            start = i;
-        } else if token.tag == POSTag::SO && open_so {
-            open_so = false;
-            if res.try_push((start, i)).is_err() {
-                return res;
-            }
-        }
+    if args.first().is_some_and(|x| x == "align") {
+        let items = process_all(&kiwi, read_titles(arg(1, "data.json")).await?).await;
+
+        let table = align::Table::build(&kiwi, &items)?;
+        println!(
+            "aligned : {} phrases from {} pairs",
+            table.len(),
+            table.pairs
+        );
+        fs::write(
+            arg(2, "alignment.json"),
+            serde_json::to_vec_pretty(&table.to_json())?,
+        )
+        .await?;
+
+        return Ok(());
     }
 
-    res
-}
+    if args.first().is_some_and(|x| x == "vectorize") {
+        let items = process_all(&kiwi, read_titles(arg(1, "data.json")).await?).await;
 
-#[test]
-fn test_find_subtitle() -> Result<(), Box<dyn std::error::Error>> {
-    let kiwi = KiwiBuilder::new(None, Default::default())?.build(None, None)?;
+        // 이미 만든 단어 목록이 있으면 같은 공간의 벡터를 만듦
+        let path = arg(2, "vectorizer.json");
+        let vectorizer = if fs::try_exists(path).await? {
+            tfidf::Vectorizer::from_json(&serde_json::from_slice(&fs::read(path).await?)?)?
+        } else {
+            let vectorizer = tfidf::Vectorizer::fit(&kiwi, &items)?;
+            fs::write(path, serde_json::to_vec(&vectorizer.to_json())?).await?;
+            vectorizer
+        };
+        println!("terms   : {}", vectorizer.len());
 
-    let txt = U16String::from_str(
-        "비밀의 버스 투어 ~나의 버스 가이드 일지~ [korean} Himitsu no Bus Tour ~Boku no Bus Guide Nisshi~",
-    );
-    let match_options = Match::new().all_with_normailize_coda();
-    let analyzed = kiwi.analyze_w(&txt, 1, match_options, None, None)?;
-    let xs = analyzed.to_vec_w();
-    let res = find_subtitle(xs.iter());
-
-    fn start(xs: &[(U16String, Token)], res: &[(usize, usize)], i: usize) -> usize {
-        let token = xs[res[i].0].1;
-        token.chr_position
-    }
+        let mut index = index::Index::new(vectorizer.len());
+        for (id, (_, split)) in items.iter().enumerate() {
+            index.add(id, vectorizer.transform(&kiwi, split)?);
+        }
+        index.train_if_large();
+
+        // 색인에는 위치만 있으므로 제목을 함께 저장함
+        let titles = items.iter().map(|(x, _)| x).collect::<Vec<_>>();
+        fs::write(
+            arg(3, "index.json"),
+            serde_json::to_vec(&serde_json::json!({ "titles": titles, "index": index.to_json() }))?,
+        )
+        .await?;
 
-    fn end(xs: &[(U16String, Token)], res: &[(usize, usize)], i: usize) -> usize {
-        let token = xs[res[i].1].1;
-        token.chr_position + token.length
+        return Ok(());
     }
 
-    assert_eq!(
-        &txt[start(&xs, &res, 0)..end(&xs, &res, 0)].to_string()?,
-        "~나의 버스 가이드 일지~"
-    );
-    assert_eq!(
-        &txt[start(&xs, &res, 1)..end(&xs, &res, 1)].to_string()?,
-        "~Boku no Bus Guide Nisshi~"
-    );
+    if args.first().is_some_and(|x| x == "dedup") {
+        let items = process_all(&kiwi, read_titles(arg(1, "data.json")).await?).await;
+        let threshold = arg(3, "0.9").parse::<f32>()?;
 
-    Ok(())
-}
+        let vectorizer = tfidf::Vectorizer::fit(&kiwi, &items)?;
+        let vectors = items
+            .iter()
+            .map(|(_, split)| vectorizer.transform(&kiwi, split))
+            .collect::<Result<Vec<_>, _>>()?;
 
-fn find_korean<'a>(
-    xs: impl Iterator<Item = &'a (U16String, Token)> + Clone,
-    reversed: bool,
-) -> Option<usize> {
-    // let has_sso_ssc = {
-    //     let sso = xs.clone().position(|(_, t)| t.tag == POSTag::SSO);
-    //     let ssc = xs.clone().position(|(_, t)| t.tag == POSTag::SSC);
+        // 중복을 놓치지 않도록 목록으로 나누지 않고 모두 비교함
+        let mut index = index::Index::new(vectorizer.len());
+        for (id, x) in vectors.iter().enumerate() {
+            index.add(id, x.clone());
+        }
 
-    //     matches!(sso.zip(ssc), Some((sso, ssc)) if sso < ssc)
-    // };
+        let groups = dedup::group(&items, &vectors, &index, threshold);
+        println!(
+            "dedup   : {} groups, {} duplicates",
+            groups.len(),
+            groups.iter().map(|x| x.duplicates.len()).sum::<usize>()
+        );
+        fs::write(
+            arg(2, "duplicates.json"),
+            serde_json::to_vec_pretty(&dedup::to_json(&groups))?,
+        )
+        .await?;
+
+        return Ok(());
+    }
 
-    let subtitles = find_subtitle(xs.clone());
+    if args.first().is_some_and(|x| x == "similar") {
+        let query = process(&kiwi, arg(1, ""))?;
+
+        let vectorizer = tfidf::Vectorizer::from_json(&serde_json::from_slice(
+            &fs::read(arg(2, "vectorizer.json")).await?,
+        )?)?;
+        let value =
+            serde_json::from_slice::<serde_json::Value>(&fs::read(arg(3, "index.json")).await?)?;
+        let titles = value
+            .get("titles")
+            .and_then(serde_json::Value::as_array)
+            .ok_or("missing titles")?;
+        let index = value
+            .get("index")
+            .and_then(index::Index::from_json)
+            .ok_or("invalid index")?;
+
+        for (id, score) in index.search(&vectorizer.transform(&kiwi, &query)?, 10) {
+            let title = titles
+                .get(id)
+                .and_then(serde_json::Value::as_str)
+                .ok_or_else(|| format!("no title for {id}"))?;
+            println!("similar : {:.3} {}", score, title);
+        }
 
-    let mut iter = xs.enumerate().peekable();
+        return Ok(());
+    }
 
-    let mut open_ss = false;
-    let mut open_so = false;
-    let mut subtitle_count = 0;
-    let mut start_episode = false;
+    if args.first().is_some_and(|x| x == "suggest") {
+        let table = read_alignment(arg(2, "alignment.json")).await?;
 
-    loop {
-        let (curr_i, (_curr_form, curr_token)) = iter.next()?;
+        for (x, split) in process_all(&kiwi, read_titles(arg(1, "data.json")).await?).await {
+            if !split.korean.is_empty() {
+                continue;
+            }
 
-        if open_so {
-            subtitle_count += 1;
+            // 대응표에 없으면 소리대로 옮김
+            let suggested = table.suggest(&split.foreign);
+            if suggested.is_empty() {
+                println!(
+                    "suggest : {} -> {} (transliterated)",
+                    x,
+                    romaji::to_hangul(&split.foreign)
+                );
+            } else {
+                println!("suggest : {} -> {}", x, suggested.join(" "));
+            }
         }
 
-        if is_korean(&curr_token.tag) {
-            if let Some((next_i, (_next_form, next_token))) = iter.peek() {
-                if is_special(&next_token.tag) && next_token.tag != POSTag::SP && !open_ss {
-                    if next_token.tag == POSTag::SN {
-                        return Some(curr_i);
-                    } else {
-                        return Some(*next_i);
-                    }
-                } else {
-                    return Some(curr_i);
-                }
-            }
-        } else {
-            if is_special(&curr_token.tag) && curr_token.tag != POSTag::SP && !open_ss {
-                if let Some((next_i, (_next_form, next_token))) = iter.peek() {
-                    // 역방향인 경우에 일부 보정이 필요함
-                    //
-                    // 정방향일 경우에는 한국어가 뒤에 있기 때문에 보정이 필요 없지만
-                    // 역방향일 경우에는 외국어를 먼저 마주하기 때문에 보정이 필요함
-                    if reversed {
-                        // 역방향일 경우, range episode가 끝까지 추출되지 않는 문제를 해결함
-                        if start_episode
-                            && curr_token.tag == POSTag::SO
-                            && next_token.tag == POSTag::SN
-                        {
-                            return Some(*next_i - 2);
-                        } else {
-                            start_episode = false;
-                        }
-
-                        // 역방향일 경우, 이른 subtitle 추출을 방지함
-                        // 해당 조건이 없으면 한국어 제목과 부제목이 모두 날아가고, 외국어만 추출됨
-                        if curr_token.tag == POSTag::SO && subtitle_count > 0 {
-                            match subtitles.get(1) {
-                                Some((_s, e)) if *e != curr_i => {}
-                                _ => {
-                                    return Some(curr_i - subtitle_count);
-                                }
-                            }
-                        }
-                    }
+        return Ok(());
+    }
 
-                    if is_korean(&next_token.tag) && !open_so {
-                        if !reversed
-                            && (curr_token.tag == POSTag::SF || curr_token.tag == POSTag::SW)
-                        {
-                        } else {
-                            return Some(curr_i);
-                        }
-                    }
-                }
-            }
+    if args.first().is_some_and(|x| x == "romanize") {
+        let text = args[1..].join(" ");
+        println!("revised : {}", romanize::romanize(&text));
+        println!(
+            "mr      : {}",
+            romanize::romanize_with(&text, romanize::System::McCuneReischauer)
+        );
 
-            // TODO: 여는 부호와 닫는 부호가 둘 다 있는지 체크해야함?
-            if curr_token.tag == POSTag::SSO || curr_token.tag == POSTag::SSC {
-                open_ss = !open_ss;
-            }
+        return Ok(());
+    }
 
-            if curr_token.tag == POSTag::SO {
-                // 부제목 토큰 개수 초기화
-                if open_so && subtitle_count > 0 {
-                    subtitle_count = 0;
+    let xs = read_titles(arg(0, "data.json")).await?;
+
+    let mut options = Options::default();
+    if let Some(path) = args.get(1) {
+        options.alignment = Some(read_alignment(path).await?);
+    }
+    let options = &options;
+
+    stream::iter(xs)
+        // .take()
+        .for_each_concurrent(6, |x| {
+            let kiwi = kiwi.clone();
+            async move {
+                if !is_supported(&x) {
+                    return;
                 }
 
-                open_so = !open_so;
+                let split = process_with(&kiwi, &x, options).unwrap();
+                if let Some(x) = &split.consistency {
+                    println!(
+                        "pair    : {:?} {:.2} (transliteration {:.2}, numbers {:?}, latin {:?}, alignment {:?})",
+                        x.verdict, x.score, x.transliteration, x.numbers, x.latin, x.alignment
+                    );
+                }
             }
+        })
+        .await;
 
-            if !start_episode && curr_token.tag == POSTag::SN {
-                start_episode = true;
-            }
-        }
-    }
+    Ok(())
+}
+
+fn is_korean(pos_tag: &POSTag) -> bool {
+    !(POSTag::SF..=POSTag::W_EMOJI).contains(pos_tag)
 }
 
 const PIPE_CHARS: [&U16Str; 4] = [u16str!("│"), u16str!("|"), u16str!("｜"), u16str!("ㅣ")];
@@ -208,7 +308,65 @@ fn is_pipe(x: impl AsRef<U16Str>) -> bool {
     PIPE_CHARS.contains(&x.as_ref())
 }
 
-fn process(kiwi: &Kiwi, text: &str) -> Result<(String, String), Box<dyn std::error::Error>> {
+#[derive(Debug, Clone)]
+pub struct Split {
+    /// 작품 제목인지, 이미지셋이나 후원 보상인지
+    pub classification: Classification,
+    pub foreign: String,
+    pub korean: String,
+    /// 장식 문자를 지운 검색용 제목
+    pub normalized: Normalized,
+    /// 한국어 제목이 외국어 제목을 소리대로 옮긴 것인지 (`Kamimachi` `카미마치`)
+    pub transliterated: bool,
+    /// 양쪽 언어가 모두 있을 때 두 제목이 같은 작품을 가리키는지
+    pub consistency: Option<Consistency>,
+    /// 원문 순서대로 정렬된 언어 구간
+    pub runs: Vec<Run>,
+    pub episodes: Vec<Episode>,
+    /// 제목 앞 묶음을 제외한 부분의 괄호
+    pub brackets: Vec<Bracket>,
+    /// 각 부분의 가장자리에서 제거된 문자열
+    pub trimmed: Vec<Trimmed>,
+    /// `[Korean]` `(decensored)` 같은 태그로부터 모은 작품 정보
+    pub release: Release,
+    /// `(Blue Archive)` 처럼 부분의 끝에 있는 원작 표기
+    pub parody: Option<Parody>,
+    /// `(C103)` 처럼 제목 앞 묶음에 있는 행사
+    pub event: Option<Event>,
+    pub circle: Option<String>,
+    pub artists: Vec<String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Normalized {
+    pub foreign: String,
+    pub korean: String,
+    /// 한국어 제목의 로마자 표기, 라틴 문자 검색과 정렬에 씀 (`huinyeoul`)
+    pub romanized: String,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Options {
+    pub trim: TrimOptions,
+    /// 원작 표기를 찾을 때 쓰는 알려진 원작 목록
+    pub franchises: Franchises,
+    /// 제목 앞 행사 표기를 알아보는 데 쓰는 표
+    pub events: Events,
+    /// 두 제목이 같은 작품을 가리키는지 확인하는 데 쓰는 대응표, 없으면 대응표 없이 확인함
+    pub alignment: Option<align::Table>,
+}
+
+fn process(kiwi: &Kiwi, text: &str) -> Result<Split, Box<dyn std::error::Error>> {
+    process_with(kiwi, text, &Options::default())
+}
+
+fn process_with(
+    kiwi: &Kiwi,
+    text: &str,
+    options: &Options,
+) -> Result<Split, Box<dyn std::error::Error>> {
+    let classification = classify::classify(text);
+
     let text = U16String::from_str(text);
     let text = text.as_ustr();
 
@@ -219,198 +377,195 @@ fn process(kiwi: &Kiwi, text: &str) -> Result<(String, String), Box<dyn std::err
 
     let analyzed = kiwi.analyze_w(text, 1, match_options, None, None)?;
 
-    let xs = analyzed.to_vec_w();
+    let mut xs = analyzed.to_vec_w();
 
     for (form, token) in &xs {
         print!("\"{}\" {} / ", form.display(), token.tag);
     }
     println!();
 
-    let mut reverse = false;
+    let brackets = bracket::match_brackets(&xs);
 
-    // count(S..=W)
-    let mut fr_count = 0;
-    let mut has_ko = false;
-    let mut has_pipe = false;
+    // 제목 앞의 `(C103) [Circle (Artist)]` 는 나누기 전에 제거함
+    let credit = credit::leading(text, &xs, &brackets, &options.events);
+    xs.drain(credit.tokens.clone());
+    let brackets = bracket::match_brackets(&xs);
 
-    let mut ko_start = 0;
-    let first_korean = find_korean(xs.iter(), false);
+    let segmentation = segment::segment(&xs, &brackets);
+    let release = Release::new(tag::find(text, &xs, &brackets));
 
-    for (i, (form, token)) in xs.iter().enumerate() {
-        if is_korean(&token.tag) || has_ko {
-            fr_count = 0;
-            has_ko = true;
-        } else {
-            fr_count += 1;
-        }
+    println!("score   : {:.5}", segmentation.score);
 
-        if is_pipe(form) {
-            if has_pipe {
-                continue;
-            }
-
-            // 해당 반복문은 `foreign | 한글` 에서 한글을 추출함
-            // pipe를 만나기도 전에 한글이 있으면 반대로 돌아야함
-            if has_ko {
-                fr_count = 0;
-                reverse = true;
-                break;
-            }
-
-            has_pipe = true;
-            fr_count -= 1;
-
-            let score = fr_count as f32 / i as f32;
-
-            // println!("score   : {:.5} / {}", score, form.display());
+    println!("origin  : {}", text.display());
+    let mut trimmed = Vec::new();
+    let mut parodies = Vec::new();
+    let mut assemble = |side: Side| {
+        segmentation
+            .spans(side)
+            .map(|range| {
+                let range = trim::trim(text, &xs, &brackets, range, &options.trim, &mut trimmed);
+                let found = parody::strip(
+                    text,
+                    &xs,
+                    &brackets,
+                    range.clone(),
+                    &options.franchises,
+                    side,
+                );
+
+                // 원작 표기 앞에 남은 구분자나 태그를 다시 제거함
+                match found {
+                    Some((parody, range)) => {
+                        parodies.push(parody);
+                        trim::trim(text, &xs, &brackets, range, &options.trim, &mut trimmed)
+                    }
+                    None => range,
+                }
+            })
+            .map(|range| text[range].to_string().unwrap().trim().to_owned())
+            .filter(|x| !x.is_empty())
+            .collect::<Vec<_>>()
+            .join(" ")
+    };
 
-            if score >= 1.0 {
-                ko_start = token.chr_position + token.length;
-            } else {
-                break;
-            }
+    let mut foreign = assemble(Side::Foreign);
+    let mut korean = assemble(Side::Korean);
+
+    let mut episodes = segmentation
+        .runs
+        .iter()
+        .flat_map(|run| episode::extract(text, &xs, run.span.clone(), run.side))
+        .collect::<Vec<_>>();
+
+    // 외국어에만 있고, 한국어에는 없는 에피소드를 가져옴 (반대의 경우도)
+    if !foreign.is_empty() && !korean.is_empty() {
+        let propagated = episode::propagate(&episodes);
+
+        for x in &propagated {
+            let title = match x.side {
+                Side::Foreign => &mut foreign,
+                Side::Korean => &mut korean,
+            };
+            title.push(' ');
+            title.push_str(&x.text);
         }
 
-        match first_korean {
-            Some(first_korean) if !has_pipe && i < first_korean => {
-                if has_ko {
-                    fr_count = 0;
-                    reverse = true;
-                    break;
-                }
-
-                let score = fr_count as f32 / i as f32;
+        episodes.extend(propagated);
+    }
 
-                // println!("score   : {:.5} / {}", score, form.display());
+    let normalized = Normalized {
+        foreign: normalize::normalize(&foreign),
+        korean: normalize::normalize(&korean),
+        romanized: normalize::normalize(&romanize::romanize(&korean)),
+    };
 
-                if score >= 1.0 {
-                    ko_start = token.chr_position + token.length;
-                } else {
-                    break;
-                }
-            }
-            Some(0) => {
-                if has_ko {
-                    fr_count = 0;
-                    reverse = true;
-                    break;
-                }
-            }
-            _ => {}
+    if korean.is_empty() {
+        println!("foreign : {}", foreign);
+    } else {
+        if !foreign.is_empty() {
+            println!("foreign : {}", foreign);
         }
+        println!("korean  : {}", korean);
+        println!("roman   : {}", normalized.romanized);
     }
 
-    let mut ko_end = 0;
-
-    if reverse {
-        let last_korean = find_korean(xs.iter().rev(), true).map(|pos| xs.len() - pos);
-
-        let mut ko_end_index = 0;
-        ko_end = {
-            let last = xs.last().unwrap().1;
-            last.chr_position + last.length
-        };
-        for (i, (form, token)) in xs.iter().enumerate() {
-            if is_korean(&token.tag) || has_ko {
-                fr_count = 0;
-                has_ko = true;
-            } else {
-                fr_count += 1;
-            }
-
-            if is_pipe(form) {
-                if has_pipe {
-                    continue;
-                }
-
-                has_pipe = true;
-                fr_count -= 1;
-
-                ko_end = token.chr_position;
-                ko_end_index = i;
-            }
-
-            match last_korean {
-                Some(last_korean) if !has_pipe && i < last_korean => {
-                    let score = fr_count as f32 / i as f32;
-
-                    // println!("score   : {:.5} / {}", score, form.display());
-
-                    if score <= 0.0 {
-                        ko_end = token.chr_position + token.length;
-                        ko_end_index = i;
-                    }
-                }
-                _ => {}
-            }
-        }
+    for x in &episodes {
+        let total = x.total.map(|n| format!("/{n}")).unwrap_or_default();
+        println!(
+            "episode : {:?} {}~{}{} {} @{:?} ({:?} <- {:?})",
+            x.kind, x.start, x.end, total, x.text, x.span, x.side, x.origin
+        );
+    }
 
-        let score = (xs.len() - ko_end_index) as f32 / fr_count as f32;
+    for x in &trimmed {
+        println!("trimmed : {:?} {} @{:?}", x.reason, x.text, x.span);
+    }
 
-        // println!("score   : {:.5}", score,);
+    if let Some(x) = &credit.event {
+        let info = x
+            .number
+            .map(|n| format!("#{n}"))
+            .into_iter()
+            .chain(x.year.map(|n| n.to_string()))
+            .chain(x.season.map(|n| format!("{n:?}")))
+            .collect::<Vec<_>>();
+        println!("event   : {} {} ({})", x.name, info.join(" "), x.text);
+    }
+    if let Some(x) = &credit.circle {
+        println!("circle  : {}", x);
+    }
+    if !credit.artists.is_empty() {
+        println!("artists : {}", credit.artists.join(", "));
+    }
 
-        if score < 1.0 {
-            ko_end = 0;
-        }
+    for x in &parodies {
+        println!(
+            "parody  : {} {} @{:?} ({:?})",
+            x.name, x.text, x.span, x.side
+        );
     }
 
-    println!("reverse : {}", reverse);
+    for x in &release.tags {
+        println!("tag     : {:?} {}", x.kind, x.text);
+    }
 
-    println!("origin  : {}", text.display());
-    let res = if reverse {
-        if has_ko {
-            let fr_start = ko_end;
-            let fr = text[fr_start..].to_string().unwrap().trim().to_owned();
-            let ko = text[..ko_end].to_string().unwrap().trim().to_owned();
-
-            if !fr.is_empty() {
-                println!("foreign : {}", fr);
-            }
-            println!("korean  : {}", ko);
+    let flags = [
+        (release.colorized, "colorized"),
+        (release.digital, "digital"),
+        (release.textless, "textless"),
+    ];
+    let info = release
+        .language
+        .map(str::to_owned)
+        .into_iter()
+        .chain(release.censorship.map(|x| format!("{x:?}")))
+        .chain(flags.iter().filter(|(x, _)| *x).map(|(_, x)| x.to_string()))
+        .chain(release.hashtags.iter().map(|x| format!("#{x}")))
+        .collect::<Vec<_>>();
+    if !info.is_empty() {
+        println!("release : {}", info.join(" "));
+    }
 
-            (fr, ko)
-        } else {
-            println!("foreign : {}", text.display());
-            (text.to_string().unwrap(), String::new())
-        }
-    } else if has_ko {
-        let fr_end = if has_pipe { ko_start - 1 } else { ko_start };
-        let fr = text[..fr_end].to_string().unwrap().trim().to_owned();
-        let ko = text[ko_start..].to_string().unwrap().trim().to_owned();
+    println!("----------------------------");
 
-        if !fr.is_empty() {
-            println!("foreign : {}", fr);
-        }
-        println!("korean  : {}", ko);
+    let transliterated = !foreign.is_empty() && romaji::is_transliteration(&foreign, &korean);
+    if transliterated {
+        println!("translit: {} -> {}", foreign, korean);
+    }
 
-        (fr, ko)
-    } else {
-        println!("foreign : {}", text.display());
-        (text.to_string().unwrap(), String::new())
+    let mut split = Split {
+        classification,
+        foreign,
+        korean,
+        normalized,
+        transliterated,
+        runs: segmentation.runs,
+        episodes,
+        brackets,
+        trimmed,
+        release,
+        // 알려진 원작을 먼저 씀
+        parody: parodies
+            .iter()
+            .find(|x| x.known)
+            .or(parodies.first())
+            .cloned(),
+        event: credit.event,
+        circle: credit.circle,
+        artists: credit.artists,
+        consistency: None,
     };
 
-    println!("----------------------------");
+    // 대응표가 없어도 음역, 에피소드, 라틴 문자 단어로 확인함
+    if !split.foreign.is_empty() && !split.korean.is_empty() {
+        let empty = align::Table::default();
+        let table = options.alignment.as_ref().unwrap_or(&empty);
+        split.consistency = Some(consistency::check(kiwi, &split, table)?);
+    }
 
-    Ok(res)
+    Ok(split)
 }
 
-// TODO: 쓸데없는 문자 제거
-// 토끼 구멍에 빠지다 (Blue Archive) [Korean} <- [Korean}
-// 슈텐도지 (decensored) <- (decensored) / 제거하기 전에 작품 정보에 검열되지 않았다는 것을 표기해야함 (uncensored)
-// 울보 공주와 사천왕 시오후키 섹스 4번 승부 [Korean]
-//
-// TODO: 외국어 제목에만 에피소드 숫자가 있는 경우
-// foreign : Haha to Ochite Iku Part 2
-// korean  : 엄마와 함께 타락해 간다
-//
-// TODO: 한국어 제목에만 에피소드 숫자가 있는 경우
-// foreign : Pokemon SV MTR
-// korean  : 포켓몬 SV MTR 6-7
-//
-// TODO: 에피소드는 아니지만
-// foreign : Mama Mansion! Dainiwa 601 Goushitsu Sonosaki Kaoru (33)
-// korean  : 마마 맨션! 제2화 601호실 소노자키 카오루
-//
 // origin  : 흰여울 _ Huin_Yeou
 // foreign : Huin_Yeou
 // korean  : 흰여울 _
@@ -423,19 +578,7 @@ fn process(kiwi: &Kiwi, text: &str) -> Result<(String, String), Box<dyn std::err
 // foreign : Zemi no Bounenkai (Zenpen)
 // korean  : 세미나 송년회 (decensored)
 //
-// "Miru" SL / "Harumin" SL / "!" SF / "To" SL / "Harent" SL / "#2" W_HASHTAG / "|" SW / "투하트" NNP /
-// reverse : false
-// origin  : Miru Harumin! To Harent#2 | 투하트
-// foreign : Miru Harumin! To Harent#2
-// korean  : 투하트
-//
-// 에피소드가 한국어에만 있는 건 상관 없음
-// 외국어에만 있고, 한국어에만 없는 걸 찾아내서 가져와야함
-// 규칙을 새로 만들기: vol vol. ch ch. part 뒤에 숫자가 있는 경우 또는 (Zenpen) 같은 것들 => H_EP
-//
-// ch.1 Vol.05 (Zenpen) 전편
-//
-// 이미지셋은 지원하지 않는 걸로 하자. 이런 거 너무 많음
+// 이미지셋은 지원하지 않는 걸로 하자. 이런 거 너무 많음 (classify 에서 걸러냄)
 // origin  : KissNTR.Gold 3.일러모음
 // foreign : KissNTR.Gold
 // korean  : 3.일러모음
@@ -491,7 +634,7 @@ fn process(kiwi: &Kiwi, text: &str) -> Result<(String, String), Box<dyn std::err
 // foreign : SEX~
 // korean  : Ano! Okaa-san no Shousai - Manatsu no Oyako SEX l 그! 엄마의 상세 ~한여름의 모자
 //
-// 이런 식으로 같은 제목임에도 한국어 제목이 특정 작품에만 없는 경우, 한국어 제목을 생성해줘야함
+// 이런 식으로 같은 제목임에도 한국어 제목이 특정 작품에만 없는 경우, 한국어 제목을 생성해줘야함 (series::fill_missing 에서 처리함)
 // "Expert ni Narimashita! 5 | 전문가가 되었습니다! 5",
 // "Expert ni Narimashita! 4",
 // "Expert ni Narimashita! 3 | 전문가가 되었습니다! 3",
@@ -506,41 +649,56 @@ fn foreign_only() -> Result<(), Box<dyn std::error::Error>> {
 
     let txt = "AZA!!🔞";
     let res = process(&kiwi, txt)?;
-    assert_eq!(res.0, txt);
-    assert!(res.1.is_empty());
+    assert_eq!(res.foreign, txt);
+    assert!(res.korean.is_empty());
 
     let txt = "Patreon 2019/02~2025/02 Tier2 Reward";
     let res = process(&kiwi, txt)?;
-    assert_eq!(res.0, txt);
-    assert!(res.1.is_empty());
+    assert_eq!(res.foreign, txt);
+    assert!(res.korean.is_empty());
+    assert_eq!(res.classification.kind, EntryKind::RewardPack);
 
     let txt = "Senko & Shiro X Horse | Senko & Shiro X Horse";
     let res = process(&kiwi, txt)?;
-    assert_eq!(res.0, txt);
-    assert!(res.1.is_empty());
+    assert_eq!(res.foreign, txt);
+    assert!(res.korean.is_empty());
 
     Ok(())
 }
 
 #[test]
 fn range_episode() -> Result<(), Box<dyn std::error::Error>> {
+    use crate::episode::EpisodeKind;
+
     let kiwi = KiwiBuilder::new(None, Default::default())?.build(None, None)?;
 
     let txt = "미유 쨩이 선생님의 육단지 오나펫이 되는 이야기 1~13 Miyu-chan ga Sensei no Nikutsubo Onapet ni Naru Hanashi";
     let res = process(&kiwi, txt)?;
     assert_eq!(
-        res.0,
-        "Miyu-chan ga Sensei no Nikutsubo Onapet ni Naru Hanashi"
+        res.foreign,
+        "Miyu-chan ga Sensei no Nikutsubo Onapet ni Naru Hanashi 1~13"
+    );
+    assert_eq!(
+        res.korean,
+        "미유 쨩이 선생님의 육단지 오나펫이 되는 이야기 1~13"
     );
-    assert_eq!(res.1, "미유 쨩이 선생님의 육단지 오나펫이 되는 이야기 1~13");
 
     let txt = "Miyu-chan ga Sensei no Nikutsubo Onapet ni Naru Hanashi 미유 쨩이 선생님의 육단지 오나펫이 되는 이야기 1~24 ";
     let res = process(&kiwi, txt)?;
     assert_eq!(
-        res.0,
-        "Miyu-chan ga Sensei no Nikutsubo Onapet ni Naru Hanashi"
+        res.foreign,
+        "Miyu-chan ga Sensei no Nikutsubo Onapet ni Naru Hanashi 1~24"
+    );
+    assert_eq!(
+        res.korean,
+        "미유 쨩이 선생님의 육단지 오나펫이 되는 이야기 1~24"
     );
-    assert_eq!(res.1, "미유 쨩이 선생님의 육단지 오나펫이 되는 이야기 1~24");
+    assert_eq!(res.episodes.len(), 2);
+    assert_eq!(res.episodes[0].kind, EpisodeKind::Range);
+    assert_eq!((res.episodes[0].start, res.episodes[0].end), (1, 24));
+    assert_eq!(res.episodes[0].side, Side::Korean);
+    assert_eq!(res.episodes[1].side, Side::Foreign);
+    assert_eq!(res.episodes[1].origin, Side::Korean);
 
     Ok(())
 }
@@ -551,9 +709,9 @@ fn korean_only_with_subtitle() -> Result<(), Box<dyn std::error::Error>> {
 
     let txt = "그녀들의 말할 수 없는 비밀 - White Lie & Dark Truth -";
     let res = process(&kiwi, txt)?;
-    assert!(res.0.is_empty());
+    assert!(res.foreign.is_empty());
     assert_eq!(
-        res.1,
+        res.korean,
         "그녀들의 말할 수 없는 비밀 - White Lie & Dark Truth -"
     );
 
@@ -566,17 +724,17 @@ fn foreign_ends_with_special() -> Result<(), Box<dyn std::error::Error>> {
 
     let txt = "Onee-chan ni Sennou Sarechau! 누나에게 세뇌당해 버려!";
     let res = process(&kiwi, txt)?;
-    assert_eq!(res.0, "Onee-chan ni Sennou Sarechau!");
-    assert_eq!(res.1, "누나에게 세뇌당해 버려!");
+    assert_eq!(res.foreign, "Onee-chan ni Sennou Sarechau!");
+    assert_eq!(res.korean, "누나에게 세뇌당해 버려!");
 
     let txt =
         "Dekachin Sokuochi Gal Succubus + W Succubus to Houkago H + 지뢰계 서큐버스의 변태 간병";
     let res = process(&kiwi, txt)?;
     assert_eq!(
-        res.0,
-        "Dekachin Sokuochi Gal Succubus + W Succubus to Houkago H +"
+        res.foreign,
+        "Dekachin Sokuochi Gal Succubus + W Succubus to Houkago H"
     );
-    assert_eq!(res.1, "지뢰계 서큐버스의 변태 간병");
+    assert_eq!(res.korean, "지뢰계 서큐버스의 변태 간병");
 
     Ok(())
 }
@@ -587,14 +745,13 @@ fn korean_only() -> Result<(), Box<dyn std::error::Error>> {
 
     let txt = "봇치님의 변태여친 1";
     let res = process(&kiwi, txt)?;
-    assert_eq!(res.1, txt);
-    assert!(res.0.is_empty());
+    assert_eq!(res.korean, txt);
+    assert!(res.foreign.is_empty());
 
-    // FIXME:
-    // let txt = "풍기위원 쿠로이와 리호코의 경우 | 풍기위원 쿠로이와 리호코의 경우";
-    // let res = process(&kiwi, txt)?;
-    // assert_eq!(txt, res.1);
-    // assert!(res.0.is_empty());
+    let txt = "풍기위원 쿠로이와 리호코의 경우 | 풍기위원 쿠로이와 리호코의 경우";
+    let res = process(&kiwi, txt)?;
+    assert_eq!(txt, res.korean);
+    assert!(res.foreign.is_empty());
 
     Ok(())
 }
@@ -606,33 +763,82 @@ fn korean_only_with_sw() -> Result<(), Box<dyn std::error::Error>> {
     // "애액" NNP / "스노우" NNP / "볼" NNG / "🎄" SW /
     let txt = "애액 스노우볼🎄";
     let res = process(&kiwi, txt)?;
-    assert_eq!(res.1, txt);
-    assert!(res.0.is_empty());
+    assert_eq!(res.korean, txt);
+    assert!(res.foreign.is_empty());
+    assert_eq!(res.normalized.korean, "애액 스노우볼");
+    assert_eq!(res.normalized.romanized, "aeaek seunoubol");
 
     Ok(())
 }
 
 #[test]
 fn open_and_close_ss() -> Result<(), Box<dyn std::error::Error>> {
+    use crate::trim::TrimReason;
+
     let kiwi = KiwiBuilder::new(None, Default::default())?.build(None, None)?;
 
     // normal
     let txt = "Himitsu no Bus Tour ~Boku no Bus Guide Nisshi~ [korean] 비밀의 버스 투어 ~나의 버스 가이드 일지~";
     let res = process(&kiwi, txt)?;
     assert_eq!(
-        res.0,
-        "Himitsu no Bus Tour ~Boku no Bus Guide Nisshi~ [korean]"
+        res.foreign,
+        "Himitsu no Bus Tour ~Boku no Bus Guide Nisshi~"
     );
-    assert_eq!(res.1, "비밀의 버스 투어 ~나의 버스 가이드 일지~");
+    assert_eq!(res.korean, "비밀의 버스 투어 ~나의 버스 가이드 일지~");
+    assert_eq!(res.trimmed.len(), 1);
+    assert_eq!(res.trimmed[0].reason, TrimReason::LanguageMarker);
+    assert_eq!(res.trimmed[0].text, "[korean]");
+    assert_eq!(res.release.language, Some("ko"));
 
     // reverse
     let txt = "비밀의 버스 투어 ~나의 버스 가이드 일지~ [korean} Himitsu no Bus Tour ~Boku no Bus Guide Nisshi~";
     let res = process(&kiwi, txt)?;
     assert_eq!(
-        res.0,
-        "[korean} Himitsu no Bus Tour ~Boku no Bus Guide Nisshi~"
+        res.foreign,
+        "Himitsu no Bus Tour ~Boku no Bus Guide Nisshi~"
+    );
+    assert_eq!(res.korean, "비밀의 버스 투어 ~나의 버스 가이드 일지~");
+    assert_eq!(res.brackets.len(), 1);
+    assert!(res.brackets[0].is_matched() && res.brackets[0].mismatched);
+    assert_eq!(res.trimmed[0].text, "[korean}");
+    assert_eq!(res.release.language, Some("ko"));
+
+    // unclosed
+    let txt = "울보 공주와 사천왕 시오후키 섹스 4번 승부 [Korean";
+    let res = process(&kiwi, txt)?;
+    assert_eq!(res.korean, "울보 공주와 사천왕 시오후키 섹스 4번 승부");
+    assert!(res.foreign.is_empty());
+    assert_eq!(res.release.language, Some("ko"));
+
+    Ok(())
+}
+
+#[test]
+fn brackets() -> Result<(), Box<dyn std::error::Error>> {
+    use bracket::BracketKind;
+
+    let kiwi = KiwiBuilder::new(None, Default::default())?.build(None, None)?;
+
+    // 제목 앞 묶음의 괄호는 없음
+    let txt = "(C103) [Circle (Artist)] Himitsu 「Ura (Omake)」 [korean}";
+    let res = process(&kiwi, txt)?;
+    assert_eq!(res.brackets.len(), 3);
+
+    let kinds = res.brackets.iter().map(|x| x.kind).collect::<Vec<_>>();
+    assert_eq!(
+        kinds,
+        [BracketKind::Corner, BracketKind::Round, BracketKind::Square]
     );
-    assert_eq!(res.1, "비밀의 버스 투어 ~나의 버스 가이드 일지~");
+    assert_eq!(
+        res.brackets.iter().map(|x| x.depth).collect::<Vec<_>>(),
+        [0, 1, 0]
+    );
+    assert!(
+        res.brackets[..2]
+            .iter()
+            .all(|x| x.is_matched() && !x.mismatched)
+    );
+    assert!(res.brackets[2].is_matched() && res.brackets[2].mismatched);
 
     Ok(())
 }
@@ -643,8 +849,18 @@ fn normal() -> Result<(), Box<dyn std::error::Error>> {
 
     let txt = "Gakuen IDOLM@STER Fundoshi Goudou | 학원 아이돌마스터 훈도시 합동";
     let res = process(&kiwi, txt)?;
-    assert_eq!(res.0, "Gakuen IDOLM@STER Fundoshi Goudou");
-    assert_eq!(res.1, "학원 아이돌마스터 훈도시 합동");
+    assert_eq!(res.foreign, "Gakuen IDOLM@STER Fundoshi Goudou");
+    assert_eq!(res.korean, "학원 아이돌마스터 훈도시 합동");
+    assert!(!res.transliterated);
+
+    let txt = "Hamechichi! | 하메찌찌!";
+    let res = process(&kiwi, txt)?;
+    assert_eq!(res.korean, "하메찌찌!");
+    assert!(res.transliterated);
+    assert_eq!(
+        res.consistency.map(|x| x.verdict),
+        Some(consistency::Verdict::Transliteration)
+    );
 
     Ok(())
 }
@@ -657,30 +873,184 @@ fn reverse() -> Result<(), Box<dyn std::error::Error>> {
     let txt = "남친 있는 백인 코스어, 공개 엉덩이 구멍 확장 방송 / Kareshi Mochi Hakujin Layer, Koukai Ketsuana Kakuchou Haishin";
     let res = process(&kiwi, txt)?;
     assert_eq!(
-        res.0,
-        "/ Kareshi Mochi Hakujin Layer, Koukai Ketsuana Kakuchou Haishin"
+        res.foreign,
+        "Kareshi Mochi Hakujin Layer, Koukai Ketsuana Kakuchou Haishin"
+    );
+    assert_eq!(
+        res.korean,
+        "남친 있는 백인 코스어, 공개 엉덩이 구멍 확장 방송"
     );
-    assert_eq!(res.1, "남친 있는 백인 코스어, 공개 엉덩이 구멍 확장 방송");
 
     // has pipe
     let txt = "미소녀 전사 세일러 문 -유성에서 온 번식자-｜Bishoujo Senshi Sailor Moon Yuusei kara no Hanshoku-sha";
     let res = process(&kiwi, txt)?;
     assert_eq!(
-        res.0,
-        "｜Bishoujo Senshi Sailor Moon Yuusei kara no Hanshoku-sha"
+        res.foreign,
+        "Bishoujo Senshi Sailor Moon Yuusei kara no Hanshoku-sha"
     );
-    assert_eq!(res.1, "미소녀 전사 세일러 문 -유성에서 온 번식자-");
+    assert_eq!(res.korean, "미소녀 전사 세일러 문 -유성에서 온 번식자-");
 
     let txt = "있을 곳이 없어 카미마치 해본 버려진 소년의 에로망가 제2화 Ibasho ga Nai node Kamimachi shite mita Suterareta Shounen no Ero Manga 2";
     let res = process(&kiwi, txt)?;
     assert_eq!(
-        res.0,
+        res.foreign,
         "Ibasho ga Nai node Kamimachi shite mita Suterareta Shounen no Ero Manga 2"
     );
     assert_eq!(
-        res.1,
+        res.korean,
         "있을 곳이 없어 카미마치 해본 버려진 소년의 에로망가 제2화"
     );
 
     Ok(())
 }
+
+#[test]
+fn multiple_runs() -> Result<(), Box<dyn std::error::Error>> {
+    let kiwi = KiwiBuilder::new(None, Default::default())?.build(None, None)?;
+
+    use crate::tag::Censorship;
+
+    let sides = |res: &Split| res.runs.iter().map(|x| x.side).collect::<Vec<_>>();
+
+    // trailing episode and tag
+    let txt = "Hamechichi! 하메찌찌! Ch. 1 (uncensored)";
+    let res = process(&kiwi, txt)?;
+    assert_eq!(res.foreign, "Hamechichi! Ch. 1");
+    assert_eq!(res.korean, "하메찌찌! Ch. 1");
+    assert_eq!(sides(&res), [Side::Foreign, Side::Korean]);
+    assert_eq!(res.release.censorship, Some(Censorship::Uncensored));
+    assert_eq!(res.release.tags[0].text, "(uncensored)");
+
+    // foreign - korean - foreign
+    let txt = "Hamechichi! 하메찌찌! Full Color";
+    let res = process(&kiwi, txt)?;
+    assert_eq!(res.foreign, "Hamechichi! Full Color");
+    assert_eq!(res.korean, "하메찌찌!");
+    assert_eq!(sides(&res), [Side::Foreign, Side::Korean, Side::Foreign]);
+
+    // abbreviation
+    let txt = "철혈 M16";
+    let res = process(&kiwi, txt)?;
+    assert_eq!(res.korean, txt);
+    assert!(res.foreign.is_empty());
+
+    // sandwiched
+    let txt = "네게브 x 카98k / Negev x Kar98k";
+    let res = process(&kiwi, txt)?;
+    assert_eq!(res.foreign, "Negev x Kar98k");
+    assert_eq!(res.korean, "네게브 x 카98k");
+    assert_eq!(sides(&res), [Side::Korean, Side::Foreign]);
+
+    Ok(())
+}
+
+#[test]
+fn propagate_episode() -> Result<(), Box<dyn std::error::Error>> {
+    let kiwi = KiwiBuilder::new(None, Default::default())?.build(None, None)?;
+
+    // foreign only
+    let txt = "Haha to Ochite Iku Part 2 | 엄마와 함께 타락해 간다";
+    let res = process(&kiwi, txt)?;
+    assert_eq!(res.foreign, "Haha to Ochite Iku Part 2");
+    assert_eq!(res.korean, "엄마와 함께 타락해 간다 Part 2");
+    assert_eq!(res.episodes.len(), 2);
+    assert!(
+        res.episodes
+            .iter()
+            .all(|x| x.start == 2 && x.origin == Side::Foreign)
+    );
+
+    // korean only
+    let txt = "Pokemon SV MTR | 포켓몬 SV MTR 6-7";
+    let res = process(&kiwi, txt)?;
+    assert_eq!(res.foreign, "Pokemon SV MTR 6-7");
+    assert_eq!(res.korean, "포켓몬 SV MTR 6-7");
+    assert_eq!(res.episodes.len(), 2);
+    assert!(
+        res.episodes
+            .iter()
+            .all(|x| (x.start, x.end) == (6, 7) && x.origin == Side::Korean)
+    );
+
+    // both
+    let txt = "Expert ni Narimashita! 5 | 전문가가 되었습니다! 5";
+    let res = process(&kiwi, txt)?;
+    assert_eq!(res.foreign, "Expert ni Narimashita! 5");
+    assert_eq!(res.korean, "전문가가 되었습니다! 5");
+    assert!(res.episodes.iter().all(|x| x.side == x.origin));
+
+    Ok(())
+}
+
+#[test]
+fn parody() -> Result<(), Box<dyn std::error::Error>> {
+    let kiwi = KiwiBuilder::new(None, Default::default())?.build(None, None)?;
+
+    // known, followed by a tag
+    let txt = "토끼 구멍에 빠지다 (Blue Archive) [Korean}";
+    let res = process(&kiwi, txt)?;
+    assert_eq!(res.korean, "토끼 구멍에 빠지다");
+    let parody = res.parody.ok_or("parody")?;
+    assert_eq!(parody.name, "Blue Archive");
+    assert!(parody.known);
+
+    // alias, user-extended
+    let mut options = Options::default();
+    options.franchises.add("Delicious in Dungeon", ["던전밥"]);
+    let txt = "Title | 마르실의 하루 (던전밥)";
+    let res = process_with(&kiwi, txt, &options)?;
+    assert_eq!(res.korean, "마르실의 하루");
+    assert_eq!(res.parody.ok_or("parody")?.name, "Delicious in Dungeon");
+
+    // not a parody
+    let txt = "하메찌찌! Ch. 1 (uncensored)";
+    let res = process(&kiwi, txt)?;
+    assert!(res.parody.is_none());
+
+    Ok(())
+}
+
+#[test]
+fn leading_credit() -> Result<(), Box<dyn std::error::Error>> {
+    let kiwi = KiwiBuilder::new(None, Default::default())?.build(None, None)?;
+
+    let txt = "(C103) [Circle (Artist)] Himitsu no Bus Tour | 비밀의 버스 투어";
+    let res = process(&kiwi, txt)?;
+    assert_eq!(res.foreign, "Himitsu no Bus Tour");
+    assert_eq!(res.korean, "비밀의 버스 투어");
+    let event = res.event.ok_or("event")?;
+    assert_eq!(
+        (event.name.as_str(), event.number),
+        ("Comic Market", Some(103))
+    );
+    assert_eq!(res.circle.as_deref(), Some("Circle"));
+    assert_eq!(res.artists, ["Artist"]);
+
+    let txt = "[작가] 비밀의 버스 투어";
+    let res = process(&kiwi, txt)?;
+    assert_eq!(res.korean, "비밀의 버스 투어");
+    assert!(res.foreign.is_empty());
+    assert_eq!(res.artists, ["작가"]);
+
+    Ok(())
+}
+
+#[test]
+fn hashtag_episode() -> Result<(), Box<dyn std::error::Error>> {
+    use crate::episode::EpisodeKind;
+
+    let kiwi = KiwiBuilder::new(None, Default::default())?.build(None, None)?;
+
+    let txt = "Miru Harumin! To Harent#2 | 투하트";
+    let res = process(&kiwi, txt)?;
+    assert_eq!(res.foreign, "Miru Harumin! To Harent#2");
+    assert_eq!(res.korean, "투하트 #2");
+    assert!(
+        res.episodes
+            .iter()
+            .all(|x| (x.kind, x.start) == (EpisodeKind::Episode, 2))
+    );
+    assert!(res.release.hashtags.is_empty());
+
+    Ok(())
+}
@@ -0,0 +1,53 @@
+/// `♥` `☆` `🔞` `🎄` 처럼 검색에 쓸모없는 장식 문자
+pub fn is_decoration(c: char) -> bool {
+    matches!(c as u32,
+        // 화살표, 도형, 기타 기호, 딩뱃
+        0x2190..=0x21FF | 0x25A0..=0x27BF | 0x2B00..=0x2BFF
+        // 이모지
+        | 0x1F000..=0x1FAFF
+        // 이모지 변형 선택자, 결합자
+        | 0xFE0E | 0xFE0F | 0x200D | 0x20E3
+    )
+}
+
+/// 같은 뜻의 문자를 하나로 맞춤
+fn canonicalize(c: char) -> char {
+    match c {
+        // 전각 영문, 숫자, 기호
+        '！'..='～' => char::from_u32(c as u32 - 0xFEE0).unwrap_or(c),
+        '\u{3000}' => ' ',
+        '〜' | '∼' => '~',
+        '‐' | '‑' | '‒' | '–' | '—' | '―' => '-',
+        '‘' | '’' => '\'',
+        '“' | '”' => '"',
+        '・' | '･' => '·',
+        c => c,
+    }
+}
+
+/// 검색과 키에 쓰는 제목
+///
+/// 장식 문자를 지우고, 비슷한 문자를 하나로 맞추고, 소문자로 바꾸고, 공백을 하나로 줄임
+pub fn normalize(x: &str) -> String {
+    x.replace('…', "...")
+        .chars()
+        .filter(|c| !is_decoration(*c))
+        .map(canonicalize)
+        .collect::<String>()
+        .to_lowercase()
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+#[test]
+fn test_normalize() {
+    assert_eq!(normalize("애액 스노우볼🎄"), "애액 스노우볼");
+    assert_eq!(normalize("설이벗방TV♥"), "설이벗방tv");
+    assert_eq!(normalize("AZA!!🔞"), "aza!!");
+    assert_eq!(
+        normalize("Ｆｕｌｌ　Ｃｏｌｏｒ ☆ Edition"),
+        "full color edition"
+    );
+    assert_eq!(normalize("일지〜 ❤️ 2"), "일지~ 2");
+}
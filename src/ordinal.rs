@@ -0,0 +1,187 @@
+use std::sync::LazyLock;
+
+use crate::episode::EpisodeKind;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Ordinal {
+    pub kind: EpisodeKind,
+    pub number: u32,
+    /// `Kouhen` 처럼 전체 개수를 알 수 있는 경우 (2편 중 2편)
+    pub total: Option<u32>,
+}
+
+const fn ordinal(kind: EpisodeKind, number: u32, total: Option<u32>) -> Ordinal {
+    Ordinal {
+        kind,
+        number,
+        total,
+    }
+}
+
+/// 전편, 후편처럼 몇 편 중 몇 번째인지 나타내는 단어
+const PART_WORDS: [(&str, Ordinal); 21] = [
+    ("zenpen", ordinal(EpisodeKind::PrequelSequel, 1, Some(2))),
+    ("chuuhen", ordinal(EpisodeKind::PrequelSequel, 2, Some(3))),
+    ("chuhen", ordinal(EpisodeKind::PrequelSequel, 2, Some(3))),
+    ("kouhen", ordinal(EpisodeKind::PrequelSequel, 2, Some(2))),
+    ("kohen", ordinal(EpisodeKind::PrequelSequel, 2, Some(2))),
+    ("zenkan", ordinal(EpisodeKind::Volume, 1, Some(2))),
+    ("joukan", ordinal(EpisodeKind::Volume, 1, Some(2))),
+    ("jokan", ordinal(EpisodeKind::Volume, 1, Some(2))),
+    ("chuukan", ordinal(EpisodeKind::Volume, 2, Some(3))),
+    ("gekan", ordinal(EpisodeKind::Volume, 2, Some(2))),
+    ("전편", ordinal(EpisodeKind::PrequelSequel, 1, Some(2))),
+    ("상편", ordinal(EpisodeKind::PrequelSequel, 1, Some(2))),
+    ("중편", ordinal(EpisodeKind::PrequelSequel, 2, Some(3))),
+    ("후편", ordinal(EpisodeKind::PrequelSequel, 2, Some(2))),
+    ("하편", ordinal(EpisodeKind::PrequelSequel, 2, Some(2))),
+    ("상권", ordinal(EpisodeKind::Volume, 1, Some(2))),
+    ("중권", ordinal(EpisodeKind::Volume, 2, Some(3))),
+    ("하권", ordinal(EpisodeKind::Volume, 2, Some(2))),
+    ("前編", ordinal(EpisodeKind::PrequelSequel, 1, Some(2))),
+    ("後編", ordinal(EpisodeKind::PrequelSequel, 2, Some(2))),
+    ("中編", ordinal(EpisodeKind::PrequelSequel, 2, Some(3))),
+];
+
+/// `(상)` `(하)` 처럼 괄호 안에 홀로 있을 때만 에피소드로 봄
+pub const BRACKETED_PART_WORDS: [(&str, Ordinal); 3] = [
+    ("상", ordinal(EpisodeKind::PrequelSequel, 1, Some(2))),
+    ("중", ordinal(EpisodeKind::PrequelSequel, 2, Some(3))),
+    ("하", ordinal(EpisodeKind::PrequelSequel, 2, Some(2))),
+];
+
+/// `Dai-ni-wa` 의 `wa` 처럼 숫자 뒤에 붙는 단위
+const COUNTERS: [(&str, EpisodeKind); 8] = [
+    ("wa", EpisodeKind::Episode),
+    ("kai", EpisodeKind::Episode),
+    ("kan", EpisodeKind::Volume),
+    ("maki", EpisodeKind::Volume),
+    ("shou", EpisodeKind::Chapter),
+    ("sho", EpisodeKind::Chapter),
+    ("bu", EpisodeKind::Part),
+    ("hen", EpisodeKind::Part),
+];
+
+/// 1 ~ 9, 뒤에 오는 단위에 따라 촉음이 되는 경우를 포함함 (`ikkai` `rokkai` `hakkan`)
+const UNITS: [(u32, &[&str]); 9] = [
+    (1, &["ichi", "ik", "it", "is"]),
+    (2, &["ni"]),
+    (3, &["san"]),
+    (4, &["yon", "yo", "shi"]),
+    (5, &["go"]),
+    (6, &["roku", "rok"]),
+    (7, &["nana", "shichi"]),
+    (8, &["hachi", "hak", "hat", "hap"]),
+    (9, &["kyuu", "kyu", "ku"]),
+];
+
+const TENS: [&str; 3] = ["juu", "ju", "jyuu"];
+
+/// 단위 앞에서 촉음이 되는 경우 (`jukkai`)
+const TENS_SOKUON: [&str; 3] = ["juk", "jut", "jup"];
+
+/// 1 ~ 99 의 로마자 표기
+static NUMBERS: LazyLock<Vec<(String, u32)>> = LazyLock::new(|| {
+    let mut res = Vec::new();
+
+    let full = |n: u32| UNITS[n as usize - 1].1[0];
+
+    for (n, units) in UNITS {
+        for unit in units {
+            res.push((unit.to_string(), n));
+        }
+    }
+
+    for tens in 1..=9 {
+        let prefix = if tens == 1 { "" } else { full(tens) };
+
+        for ten in TENS {
+            res.push((format!("{prefix}{ten}"), tens * 10));
+
+            for (n, units) in UNITS {
+                for unit in units {
+                    res.push((format!("{prefix}{ten}{unit}"), tens * 10 + n));
+                }
+            }
+        }
+
+        for ten in TENS_SOKUON {
+            res.push((format!("{prefix}{ten}"), tens * 10));
+        }
+    }
+
+    // 긴 표기부터 비교함
+    res.sort_by_key(|(x, _)| std::cmp::Reverse(x.len()));
+
+    res
+});
+
+fn normalize(x: &str) -> String {
+    x.to_lowercase()
+        .replace('ō', "ou")
+        .replace('ū', "uu")
+        .replace(['-', '_', ' '], "")
+}
+
+/// `Dainiwa` `Daiikkai` `Dai-3-wa` `Daijuuniwa`
+fn dai(x: &str) -> Option<Ordinal> {
+    let rest = x.strip_prefix("dai")?;
+
+    let parse = |number: u32, rest: &str| {
+        COUNTERS
+            .iter()
+            .find(|(counter, _)| rest == *counter)
+            .map(|(_, kind)| ordinal(*kind, number, None))
+    };
+
+    // `Dai2wa`
+    let digits = rest.chars().take_while(char::is_ascii_digit).count();
+    if digits > 0 {
+        let number = rest[..digits].parse().ok()?;
+        return parse(number, &rest[digits..]);
+    }
+
+    NUMBERS.iter().find_map(|(spelling, number)| {
+        rest.strip_prefix(spelling.as_str())
+            .and_then(|rest| parse(*number, rest))
+    })
+}
+
+/// 로마자나 한국어로 쓰인 서수, 편 단어를 에피소드 번호로 바꿈
+pub fn parse(x: &str) -> Option<Ordinal> {
+    let x = normalize(x);
+
+    PART_WORDS
+        .iter()
+        .find(|(word, _)| *word == x)
+        .map(|(_, ordinal)| *ordinal)
+        .or_else(|| dai(&x))
+}
+
+#[test]
+fn test_parse() {
+    let parse = |x| parse(x).map(|x| (x.kind, x.number, x.total));
+
+    assert_eq!(parse("Dainiwa"), Some((EpisodeKind::Episode, 2, None)));
+    assert_eq!(parse("Daiikkai"), Some((EpisodeKind::Episode, 1, None)));
+    assert_eq!(parse("Dai-san-wa"), Some((EpisodeKind::Episode, 3, None)));
+    assert_eq!(parse("Daijuuniwa"), Some((EpisodeKind::Episode, 12, None)));
+    assert_eq!(parse("Dai2kan"), Some((EpisodeKind::Volume, 2, None)));
+    assert_eq!(parse("Daiisshou"), Some((EpisodeKind::Chapter, 1, None)));
+    assert_eq!(
+        parse("Kouhen"),
+        Some((EpisodeKind::PrequelSequel, 2, Some(2)))
+    );
+    assert_eq!(
+        parse("Zenpen"),
+        Some((EpisodeKind::PrequelSequel, 1, Some(2)))
+    );
+    assert_eq!(parse("Gekan"), Some((EpisodeKind::Volume, 2, Some(2))));
+    assert_eq!(
+        parse("하편"),
+        Some((EpisodeKind::PrequelSequel, 2, Some(2)))
+    );
+
+    assert_eq!(parse("Daisuki"), None);
+    assert_eq!(parse("Niwa"), None);
+}
@@ -0,0 +1,232 @@
+use std::ops::Range;
+
+use rkiwi::{POSTag, analyzed::Token};
+use widestring::{U16Str, U16String};
+
+use crate::{
+    bracket::{Bracket, BracketKind},
+    episode, is_korean,
+    segment::{Side, end_of},
+    tag,
+};
+
+/// 패러디 원작
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Franchise {
+    /// 대표 이름
+    pub name: String,
+    /// 한국어, 일본어, 줄임말 등 다른 이름
+    pub aliases: Vec<String>,
+}
+
+/// 알려진 원작 목록
+///
+/// `add`로 원작이나 다른 이름을 추가할 수 있음
+#[derive(Debug, Clone)]
+pub struct Franchises {
+    franchises: Vec<Franchise>,
+}
+
+const FRANCHISES: [(&str, &[&str]); 15] = [
+    ("Original", &["오리지널", "オリジナル"]),
+    (
+        "Blue Archive",
+        &["블루 아카이브", "블루아카", "ブルーアーカイブ", "ブルアカ"],
+    ),
+    ("Fate/Grand Order", &["FGO", "페이트 그랜드 오더", "페그오"]),
+    ("Genshin Impact", &["원신", "原神"]),
+    (
+        "Honkai: Star Rail",
+        &["붕괴 스타레일", "스타레일", "崩壊：スターレイル"],
+    ),
+    (
+        "Girls' Frontline",
+        &["소녀전선", "소전", "少女前線", "ドールズフロントライン"],
+    ),
+    ("Azur Lane", &["벽람항로", "アズールレーン"]),
+    ("Arknights", &["명일방주", "アークナイツ"]),
+    (
+        "Pokemon",
+        &[
+            "Pokémon",
+            "Pocket Monsters",
+            "포켓몬",
+            "포켓몬스터",
+            "ポケモン",
+        ],
+    ),
+    (
+        "Touhou Project",
+        &["Touhou", "동방", "동방 프로젝트", "東方", "東方Project"],
+    ),
+    (
+        "Uma Musume Pretty Derby",
+        &["Umamusume", "우마무스메", "ウマ娘"],
+    ),
+    (
+        "Kantai Collection",
+        &["KanColle", "칸코레", "함대 컬렉션", "艦これ"],
+    ),
+    (
+        "The Idolmaster",
+        &["THE iDOLM@STER", "아이돌마스터", "アイドルマスター"],
+    ),
+    ("Hololive", &["홀로라이브", "ホロライブ"]),
+    (
+        "Nikke",
+        &["Goddess of Victory: Nikke", "니케", "승리의 여신: 니케"],
+    ),
+];
+
+impl Default for Franchises {
+    fn default() -> Self {
+        let mut res = Self {
+            franchises: Vec::new(),
+        };
+
+        for (name, aliases) in FRANCHISES {
+            res.add(name, aliases.iter().copied());
+        }
+
+        res
+    }
+}
+
+impl Franchises {
+    /// 이미 있는 원작이면 다른 이름만 추가함
+    pub fn add<'a>(&mut self, name: &str, aliases: impl IntoIterator<Item = &'a str>) -> &mut Self {
+        let at = match self.franchises.iter().position(|x| x.name == name) {
+            Some(at) => at,
+            None => {
+                self.franchises.push(Franchise {
+                    name: name.to_owned(),
+                    aliases: Vec::new(),
+                });
+                self.franchises.len() - 1
+            }
+        };
+
+        let franchise = &mut self.franchises[at];
+        franchise
+            .aliases
+            .extend(aliases.into_iter().map(str::to_owned));
+
+        self
+    }
+
+    /// 대표 이름이나 다른 이름으로 원작을 찾음
+    ///
+    /// 대소문자, 공백과 기호는 무시함
+    pub fn find(&self, x: &str) -> Option<&Franchise> {
+        let x = normalize(x);
+
+        self.franchises.iter().find(|franchise| {
+            std::iter::once(&franchise.name)
+                .chain(&franchise.aliases)
+                .any(|name| normalize(name) == x)
+        })
+    }
+}
+
+fn normalize(x: &str) -> String {
+    x.chars()
+        .filter(|c| c.is_alphanumeric())
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Parody {
+    /// 알려진 원작이면 대표 이름, 아니면 괄호 안쪽 원문
+    pub name: String,
+    /// 괄호를 포함한 원문
+    pub text: String,
+    /// 괄호를 포함한 원문 위치 (utf-16)
+    pub span: Range<usize>,
+    /// 알려진 원작 목록에 있는지
+    pub known: bool,
+    pub side: Side,
+}
+
+/// 부분의 끝에 있는 `(Blue Archive)` 같은 원작 표기를 찾아 제거함
+///
+/// 알려진 원작이 아니면 한국어 부분 끝에 외국어로만 쓰인 경우만 원작으로 봄
+pub fn strip(
+    text: &U16Str,
+    xs: &[(U16String, Token)],
+    brackets: &[Bracket],
+    range: Range<usize>,
+    franchises: &Franchises,
+    side: Side,
+) -> Option<(Parody, Range<usize>)> {
+    let (last, _) = xs
+        .iter()
+        .enumerate()
+        .rfind(|(_, (_, t))| range.start <= t.chr_position && end_of(t) <= range.end)?;
+
+    let bracket = brackets.iter().find(|x| {
+        x.close == Some(last)
+            && x.kind == BracketKind::Round
+            && !x.mismatched
+            && x.is_matched()
+            && range.start < x.span.start
+    })?;
+    let inner = bracket.inner()?;
+
+    let name = text[bracket.span.start + 1..bracket.span.end - 1].to_string_lossy();
+    let name = name.trim();
+
+    let parody = match franchises.find(name) {
+        Some(franchise) => Parody {
+            name: franchise.name.clone(),
+            text: text[bracket.span.clone()].to_string_lossy(),
+            span: bracket.span.clone(),
+            known: true,
+            side,
+        },
+        None => {
+            let episode = episode::at(xs, inner.start).is_some_and(|x| x.next == inner.end);
+
+            let tokens = &xs[inner];
+            let foreign_only = tokens.iter().any(|(_, t)| t.tag == POSTag::SL)
+                && !tokens.iter().any(|(_, t)| is_korean(&t.tag));
+
+            if side != Side::Korean || !foreign_only || episode || tag::parse(name).is_some() {
+                return None;
+            }
+
+            Parody {
+                name: name.to_owned(),
+                text: text[bracket.span.clone()].to_string_lossy(),
+                span: bracket.span.clone(),
+                known: false,
+                side,
+            }
+        }
+    };
+
+    Some((parody, range.start..bracket.span.start))
+}
+
+#[test]
+fn test_find() {
+    let mut franchises = Franchises::default();
+
+    assert_eq!(
+        franchises.find("블루아카").map(|x| x.name.as_str()),
+        Some("Blue Archive")
+    );
+    assert_eq!(
+        franchises
+            .find("fate / grand order")
+            .map(|x| x.name.as_str()),
+        Some("Fate/Grand Order")
+    );
+    assert!(franchises.find("Dungeon Meshi").is_none());
+
+    franchises.add("Delicious in Dungeon", ["Dungeon Meshi", "던전밥"]);
+    assert_eq!(
+        franchises.find("던전밥").map(|x| x.name.as_str()),
+        Some("Delicious in Dungeon")
+    );
+}
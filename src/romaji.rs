@@ -0,0 +1,402 @@
+/// 로마자를 한글로 옮기는 방식
+///
+/// 기본값은 외래어 표기법의 일본어 표기를 따름
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Style {
+    /// 어두의 `k` `t` `ch`도 거센소리로 씀 (`카미마치`)
+    pub aspirated_initial: bool,
+    /// `ch`를 된소리로 씀 (`하메찌찌`)
+    pub tense_ch: bool,
+    /// `tsu`를 `츠`로 씀
+    pub tsu_as_cheu: bool,
+    /// `ou` `uu` `ō` 같은 장음을 지우지 않음 (`코우하이`)
+    pub long_vowels: bool,
+}
+
+const STYLES: [Style; 16] = {
+    let mut res = [Style {
+        aspirated_initial: false,
+        tense_ch: false,
+        tsu_as_cheu: false,
+        long_vowels: false,
+    }; 16];
+
+    let mut i = 0;
+    while i < 16 {
+        res[i] = Style {
+            aspirated_initial: i & 1 != 0,
+            tense_ch: i & 2 != 0,
+            tsu_as_cheu: i & 4 != 0,
+            long_vowels: i & 8 != 0,
+        };
+        i += 1;
+    }
+
+    res
+};
+
+/// 자모 번호
+const G: u32 = 0;
+const N: u32 = 2;
+const D: u32 = 3;
+const R: u32 = 5;
+const M: u32 = 6;
+const B: u32 = 7;
+const S: u32 = 9;
+const SS: u32 = 10;
+const O: u32 = 11;
+const J: u32 = 12;
+const JJ: u32 = 13;
+const CH: u32 = 14;
+const K: u32 = 15;
+const T: u32 = 16;
+const P: u32 = 17;
+const H: u32 = 18;
+
+const CODA_N: u32 = 4;
+const CODA_S: u32 = 19;
+
+#[derive(Debug, Clone, Copy)]
+struct Onset {
+    /// 어두에서의 초성
+    initial: u32,
+    /// 어중에서의 초성
+    medial: u32,
+    /// `kya` `sha` 처럼 반모음이 붙음
+    glide: bool,
+}
+
+const fn onset(initial: u32, medial: u32, glide: bool) -> Onset {
+    Onset {
+        initial,
+        medial,
+        glide,
+    }
+}
+
+/// 모음으로 시작하는 음절
+const VOWEL_ONSET: Onset = onset(O, O, false);
+
+/// 긴 것부터 찾음
+const ONSETS: [(&str, Onset); 32] = [
+    ("ky", onset(G, K, true)),
+    ("gy", onset(G, G, true)),
+    ("sh", onset(S, S, true)),
+    ("sy", onset(S, S, true)),
+    ("jy", onset(J, J, true)),
+    ("ch", onset(J, CH, true)),
+    ("ts", onset(SS, SS, false)),
+    ("ny", onset(N, N, true)),
+    ("hy", onset(H, H, true)),
+    ("by", onset(B, B, true)),
+    ("py", onset(P, P, true)),
+    ("my", onset(M, M, true)),
+    ("ry", onset(R, R, true)),
+    ("k", onset(G, K, false)),
+    ("g", onset(G, G, false)),
+    ("s", onset(S, S, false)),
+    ("z", onset(J, J, false)),
+    ("j", onset(J, J, true)),
+    ("t", onset(D, T, false)),
+    ("d", onset(D, D, false)),
+    ("n", onset(N, N, false)),
+    ("h", onset(H, H, false)),
+    ("f", onset(H, H, false)),
+    ("b", onset(B, B, false)),
+    ("v", onset(B, B, false)),
+    ("p", onset(P, P, false)),
+    ("m", onset(M, M, false)),
+    ("r", onset(R, R, false)),
+    ("l", onset(R, R, false)),
+    ("y", onset(O, O, true)),
+    ("w", onset(O, O, false)),
+    ("c", onset(G, K, false)),
+];
+
+/// 장음 기호가 붙은 모음
+const MACRONS: [(char, char); 10] = [
+    ('ā', 'a'),
+    ('ī', 'i'),
+    ('ū', 'u'),
+    ('ē', 'e'),
+    ('ō', 'o'),
+    ('â', 'a'),
+    ('î', 'i'),
+    ('û', 'u'),
+    ('ê', 'e'),
+    ('ô', 'o'),
+];
+
+fn is_vowel(c: char) -> bool {
+    matches!(c, 'a' | 'i' | 'u' | 'e' | 'o')
+}
+
+fn is_letter(c: char) -> bool {
+    c.is_ascii_alphabetic() || c == '\'' || MACRONS.iter().any(|(x, _)| *x == c)
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Syllable {
+    initial: u32,
+    medial: u32,
+    last: u32,
+}
+
+impl Syllable {
+    fn to_char(self) -> char {
+        char::from_u32(0xAC00 + (self.initial * 21 + self.medial) * 28 + self.last).unwrap()
+    }
+}
+
+/// 중성 번호
+fn medial(vowel: char, glide: bool, initial: u32, onset: &str) -> u32 {
+    // `w`는 모음과 합쳐짐
+    if onset == "w" {
+        return match vowel {
+            'a' => 9,
+            'i' => 16,
+            'e' => 15,
+            'u' => 13,
+            _ => 8,
+        };
+    }
+
+    // `す` `ず` `つ`는 `ㅡ`
+    if vowel == 'u' && matches!(onset, "s" | "z" | "ts") {
+        return 18;
+    }
+
+    // `ㅈ` `ㅊ` `ㅉ` 뒤의 반모음은 쓰지 않음
+    let glide = glide && !matches!(initial, J | JJ | CH) && vowel != 'i';
+
+    match (vowel, glide) {
+        ('a', false) => 0,
+        ('a', true) => 2,
+        ('e', false) => 5,
+        ('e', true) => 7,
+        ('o', false) => 8,
+        ('o', true) => 12,
+        ('u', false) => 13,
+        ('u', true) => 17,
+        _ => 20,
+    }
+}
+
+/// 앞 모음을 늘이는 모음인지
+fn lengthens(prev: char, vowel: char) -> bool {
+    prev == vowel || (prev == 'o' && vowel == 'u')
+}
+
+fn word(x: &[char], style: Style, out: &mut String) {
+    let mut res: Vec<Syllable> = Vec::new();
+    let mut prev_vowel = None;
+    let mut initial = true;
+    let mut i = 0;
+
+    while i < x.len() {
+        let c = x[i];
+        let next = x.get(i + 1).copied();
+
+        if c == '\'' {
+            prev_vowel = None;
+            i += 1;
+            continue;
+        }
+
+        // `ん` (`m`은 `b` `p` `m` 앞에서만)
+        let nasal = match c {
+            'n' => !next.is_some_and(|x| is_vowel(x) || x == 'y'),
+            'm' => matches!(next, Some('b' | 'p' | 'm')),
+            _ => false,
+        };
+        if nasal {
+            match res.last_mut() {
+                Some(last) if last.last == 0 => last.last = CODA_N,
+                _ => res.push(Syllable {
+                    initial: O,
+                    medial: 18,
+                    last: CODA_N,
+                }),
+            }
+            prev_vowel = None;
+            i += 1;
+            continue;
+        }
+
+        // `っ`
+        let doubled = c.is_ascii_alphabetic()
+            && !is_vowel(c)
+            && (next == Some(c) || (c == 't' && next == Some('c') && x.get(i + 2) == Some(&'h')));
+        if doubled {
+            if let Some(last) = res.last_mut().filter(|x| x.last == 0) {
+                last.last = CODA_S;
+            }
+            prev_vowel = None;
+            i += 1;
+            continue;
+        }
+
+        let found = ONSETS.iter().find(|(onset, _)| {
+            x.len() >= i + onset.len() && onset.chars().zip(&x[i..]).all(|(a, b)| a == *b)
+        });
+        let (onset, o) = match found {
+            Some((onset, o)) => (*onset, *o),
+            None => ("", VOWEL_ONSET),
+        };
+        let at = i + onset.len();
+
+        let vowel = x.get(at).copied().and_then(|c| {
+            if is_vowel(c) {
+                Some((c, false))
+            } else {
+                MACRONS
+                    .iter()
+                    .find(|(x, _)| *x == c)
+                    .map(|(_, v)| (*v, true))
+            }
+        });
+
+        let Some((vowel, long)) = vowel else {
+            // 로마자로 읽을 수 없는 문자는 앞의 음절 뒤에 그대로 씀
+            out.extend(res.drain(..).map(Syllable::to_char));
+            out.push(c);
+            prev_vowel = None;
+            initial = false;
+            i += 1;
+            continue;
+        };
+
+        // 장음
+        if onset.is_empty()
+            && !style.long_vowels
+            && prev_vowel.is_some_and(|prev| lengthens(prev, vowel))
+        {
+            prev_vowel = None;
+            i = at + 1;
+            continue;
+        }
+
+        let mut cho = if initial && !style.aspirated_initial {
+            o.initial
+        } else {
+            o.medial
+        };
+        if onset == "ch" && style.tense_ch {
+            cho = JJ;
+        }
+        if onset == "ts" && style.tsu_as_cheu {
+            cho = CH;
+        }
+
+        res.push(Syllable {
+            initial: cho,
+            medial: medial(vowel, o.glide, cho, onset),
+            last: 0,
+        });
+
+        if long && style.long_vowels {
+            let vowel = if vowel == 'o' { 'u' } else { vowel };
+            res.push(Syllable {
+                initial: O,
+                medial: medial(vowel, false, O, ""),
+                last: 0,
+            });
+        }
+
+        prev_vowel = if long { None } else { Some(vowel) };
+        initial = false;
+        i = at + 1;
+    }
+
+    out.extend(res.drain(..).map(Syllable::to_char));
+}
+
+/// 로마자를 정해진 방식으로 한글로 옮김
+///
+/// 로마자가 아닌 문자는 그대로 둠
+pub fn to_hangul_with(x: &str, style: Style) -> String {
+    let x = x.to_lowercase().chars().collect::<Vec<_>>();
+    let mut res = String::new();
+    let mut i = 0;
+
+    while i < x.len() {
+        let len = x[i..].iter().take_while(|c| is_letter(**c)).count();
+
+        if len == 0 {
+            res.push(x[i]);
+            i += 1;
+        } else {
+            word(&x[i..i + len], style, &mut res);
+            i += len;
+        }
+    }
+
+    res
+}
+
+/// `Kamimachi` → `가미마치`
+pub fn to_hangul(x: &str) -> String {
+    to_hangul_with(x, Style::default())
+}
+
+/// 표기법과 흔히 쓰이는 번역 표기, 중복은 제거됨
+pub fn variants(x: &str) -> Vec<String> {
+    let mut res: Vec<String> = Vec::new();
+
+    for style in STYLES {
+        let y = to_hangul_with(x, style);
+        if !res.contains(&y) {
+            res.push(y);
+        }
+    }
+
+    res
+}
+
+/// 한글 음절만 남김
+pub(crate) fn hangul(x: &str) -> String {
+    x.chars().filter(|c| ('가'..='힣').contains(c)).collect()
+}
+
+/// 한국어 제목이 외국어 제목을 소리대로 옮긴 것인지
+///
+/// 한글이 아닌 문자와 띄어쓰기는 무시함
+pub fn is_transliteration(foreign: &str, korean: &str) -> bool {
+    let korean = hangul(korean);
+
+    !korean.is_empty() && variants(foreign).iter().any(|x| hangul(x) == korean)
+}
+
+#[test]
+fn test_to_hangul() {
+    assert_eq!(to_hangul("Kamimachi"), "가미마치");
+    assert_eq!(to_hangul("Hamechichi!"), "하메치치!");
+    assert_eq!(to_hangul("Tōkyō"), "도쿄");
+    assert_eq!(to_hangul("Shinjuku"), "신주쿠");
+    assert_eq!(to_hangul("Kitto"), "깃토");
+    assert_eq!(to_hangul("Tsukimi"), "쓰키미");
+    assert_eq!(to_hangul("Ren'ai Shimbun"), "렌아이 신분");
+    assert_eq!(to_hangul("Kouhai 2"), "고하이 2");
+    assert_eq!(to_hangul("Onna no Ko"), "온나 노 고");
+    assert_eq!(to_hangul("Sugoi"), "스고이");
+
+    let fan = Style {
+        aspirated_initial: true,
+        tense_ch: true,
+        tsu_as_cheu: true,
+        long_vowels: true,
+    };
+    assert_eq!(to_hangul_with("Hamechichi", fan), "하메찌찌");
+    assert_eq!(to_hangul_with("Kouhai", fan), "코우하이");
+    assert_eq!(to_hangul_with("Tsukimi", fan), "츠키미");
+}
+
+#[test]
+fn test_is_transliteration() {
+    assert!(is_transliteration("Hamechichi!", "하메찌찌!"));
+    assert!(is_transliteration("Kamimachi", "카미마치"));
+    assert!(is_transliteration("Ore no Kanojo", "오레노 카노조"));
+
+    assert!(!is_transliteration("Kareshi Mochi", "남친 있는"));
+    assert!(!is_transliteration("Kamimachi", ""));
+}
@@ -0,0 +1,387 @@
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum System {
+    /// 국어의 로마자 표기법 (`huinyeoul`)
+    #[default]
+    Revised,
+    /// 매큔-라이샤워 표기법 (`hŭinyŏul`)
+    McCuneReischauer,
+}
+
+/// 받침의 대표음
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Coda {
+    K,
+    N,
+    T,
+    L,
+    M,
+    P,
+    Ng,
+}
+
+/// 초성 번호
+const G: u32 = 0;
+const KK: u32 = 1;
+const N: u32 = 2;
+const D: u32 = 3;
+const R: u32 = 5;
+const M: u32 = 6;
+const B: u32 = 7;
+const S: u32 = 9;
+const SS: u32 = 10;
+const O: u32 = 11;
+const J: u32 = 12;
+const CH: u32 = 14;
+const K: u32 = 15;
+const T: u32 = 16;
+const P: u32 = 17;
+const H: u32 = 18;
+
+/// 중성 `ㅣ` `ㅟ`
+const I: u32 = 20;
+const WI: u32 = 16;
+
+const INITIALS: [(&str, &str); 19] = [
+    ("g", "k"),
+    ("kk", "kk"),
+    ("n", "n"),
+    ("d", "t"),
+    ("tt", "tt"),
+    ("r", "r"),
+    ("m", "m"),
+    ("b", "p"),
+    ("pp", "pp"),
+    ("s", "s"),
+    ("ss", "ss"),
+    ("", ""),
+    ("j", "ch"),
+    ("jj", "tch"),
+    ("ch", "ch'"),
+    ("k", "k'"),
+    ("t", "t'"),
+    ("p", "p'"),
+    ("h", "h"),
+];
+
+const MEDIALS: [(&str, &str); 21] = [
+    ("a", "a"),
+    ("ae", "ae"),
+    ("ya", "ya"),
+    ("yae", "yae"),
+    ("eo", "ŏ"),
+    ("e", "e"),
+    ("yeo", "yŏ"),
+    ("ye", "ye"),
+    ("o", "o"),
+    ("wa", "wa"),
+    ("wae", "wae"),
+    ("oe", "oe"),
+    ("yo", "yo"),
+    ("u", "u"),
+    ("wo", "wŏ"),
+    ("we", "we"),
+    ("wi", "wi"),
+    ("yu", "yu"),
+    ("eu", "ŭ"),
+    ("ui", "ŭi"),
+    ("i", "i"),
+];
+
+/// 받침의 대표음
+const CODAS: [Option<Coda>; 28] = [
+    None,
+    Some(Coda::K),  // ㄱ
+    Some(Coda::K),  // ㄲ
+    Some(Coda::K),  // ㄳ
+    Some(Coda::N),  // ㄴ
+    Some(Coda::N),  // ㄵ
+    Some(Coda::N),  // ㄶ
+    Some(Coda::T),  // ㄷ
+    Some(Coda::L),  // ㄹ
+    Some(Coda::K),  // ㄺ
+    Some(Coda::M),  // ㄻ
+    Some(Coda::L),  // ㄼ
+    Some(Coda::L),  // ㄽ
+    Some(Coda::L),  // ㄾ
+    Some(Coda::P),  // ㄿ
+    Some(Coda::L),  // ㅀ
+    Some(Coda::M),  // ㅁ
+    Some(Coda::P),  // ㅂ
+    Some(Coda::P),  // ㅄ
+    Some(Coda::T),  // ㅅ
+    Some(Coda::T),  // ㅆ
+    Some(Coda::Ng), // ㅇ
+    Some(Coda::T),  // ㅈ
+    Some(Coda::T),  // ㅊ
+    Some(Coda::K),  // ㅋ
+    Some(Coda::T),  // ㅌ
+    Some(Coda::P),  // ㅍ
+    Some(Coda::T),  // ㅎ
+];
+
+/// 모음 앞에서 (남는 받침, 다음 음절로 넘어가는 초성)
+const LIAISONS: [(Option<Coda>, u32); 28] = [
+    (None, O),
+    (None, G),
+    (None, KK),
+    (Some(Coda::K), S),
+    (None, N),
+    (Some(Coda::N), J),
+    (None, N),
+    (None, D),
+    (None, R),
+    (Some(Coda::L), G),
+    (Some(Coda::L), M),
+    (Some(Coda::L), B),
+    (Some(Coda::L), S),
+    (Some(Coda::L), T),
+    (Some(Coda::L), P),
+    (None, R),
+    (None, M),
+    (None, B),
+    (Some(Coda::P), S),
+    (None, S),
+    (None, SS),
+    (Some(Coda::Ng), O),
+    (None, J),
+    (None, CH),
+    (None, K),
+    (None, T),
+    (None, P),
+    (None, O),
+];
+
+#[derive(Debug, Clone, Copy)]
+struct Syllable {
+    initial: u32,
+    medial: u32,
+    /// 받침 번호
+    last: u32,
+    /// 소리 나는 받침
+    coda: Option<Coda>,
+}
+
+fn decompose(c: char) -> Option<Syllable> {
+    let x = (c as u32).checked_sub(0xAC00).filter(|x| *x < 11172)?;
+    let last = x % 28;
+
+    Some(Syllable {
+        initial: x / 28 / 21,
+        medial: x / 28 % 21,
+        last,
+        coda: CODAS[last as usize],
+    })
+}
+
+/// 거센소리
+fn aspirated(initial: u32) -> u32 {
+    match initial {
+        G => K,
+        D => T,
+        B => P,
+        J => CH,
+        x => x,
+    }
+}
+
+/// 음절 사이의 연음, 거센소리되기, 비음화, 유음화, 구개음화
+fn assimilate(xs: &mut [Syllable]) {
+    for i in 1..xs.len() {
+        let (prev, next) = xs.split_at_mut(i);
+        let (a, b) = (&mut prev[i - 1], &mut next[0]);
+
+        if a.last == 0 {
+            continue;
+        }
+
+        // `ㅎ` `ㄶ` `ㅀ` 받침
+        if matches!(a.last, 6 | 15 | 27) {
+            let rest = match a.last {
+                6 => Some(Coda::N),
+                15 => Some(Coda::L),
+                _ => None,
+            };
+
+            match b.initial {
+                O => {
+                    a.coda = None;
+                    b.initial = match a.last {
+                        6 => N,
+                        15 => R,
+                        _ => O,
+                    };
+                }
+                G | D | J => {
+                    a.coda = rest;
+                    b.initial = aspirated(b.initial);
+                }
+                N if a.last == 27 => a.coda = Some(Coda::N),
+                _ => a.coda = rest,
+            }
+            continue;
+        }
+
+        if b.initial == O {
+            let (coda, initial) = LIAISONS[a.last as usize];
+            a.coda = coda;
+            b.initial = initial;
+
+            // `같이` `굳이`
+            if b.medial == I && coda.is_none() {
+                b.initial = match initial {
+                    D => J,
+                    T => CH,
+                    x => x,
+                };
+            }
+            continue;
+        }
+
+        b.initial = match (a.coda, b.initial) {
+            (Some(Coda::K | Coda::T | Coda::P), H) => {
+                let initial = match a.coda {
+                    Some(Coda::K) => K,
+                    Some(Coda::P) => P,
+                    _ if a.last == 22 || a.last == 23 => CH,
+                    _ => T,
+                };
+                a.coda = None;
+                initial
+            }
+            (Some(Coda::K | Coda::P), R) => N,
+            (Some(Coda::M | Coda::Ng), R) => N,
+            (Some(Coda::T), R) => {
+                a.coda = Some(Coda::N);
+                N
+            }
+            (Some(Coda::L), N) => R,
+            (Some(Coda::N), R) => {
+                a.coda = Some(Coda::L);
+                R
+            }
+            (_, x) => x,
+        };
+
+        // `백마` `독립`
+        if matches!(b.initial, N | M) {
+            a.coda = match a.coda {
+                Some(Coda::K) => Some(Coda::Ng),
+                Some(Coda::T) => Some(Coda::N),
+                Some(Coda::P) => Some(Coda::M),
+                x => x,
+            };
+        }
+    }
+}
+
+fn coda(x: Coda) -> &'static str {
+    match x {
+        Coda::K => "k",
+        Coda::N => "n",
+        Coda::T => "t",
+        Coda::L => "l",
+        Coda::M => "m",
+        Coda::P => "p",
+        Coda::Ng => "ng",
+    }
+}
+
+fn word(xs: &mut [Syllable], system: System, out: &mut String) {
+    assimilate(xs);
+
+    for i in 0..xs.len() {
+        let x = xs[i];
+        let prev = i.checked_sub(1).map(|i| xs[i].coda);
+
+        let initial = match (system, x.initial) {
+            // `ㄹ` 받침 뒤의 `ㄹ`은 `l`
+            (_, R) if prev == Some(Some(Coda::L)) => "l",
+            (System::Revised, x) => INITIALS[x as usize].0,
+            // 모음이나 울림소리 받침 뒤의 예사소리는 유성음
+            (System::McCuneReischauer, G | D | B | J)
+                if prev.is_some_and(|x| {
+                    matches!(x, None | Some(Coda::N | Coda::L | Coda::M | Coda::Ng))
+                }) =>
+            {
+                INITIALS[x.initial as usize].0
+            }
+            (System::McCuneReischauer, S) if matches!(x.medial, I | WI) => "sh",
+            (System::McCuneReischauer, x) => INITIALS[x as usize].1,
+        };
+
+        // `n'g` 와 `ng` 를 구별함
+        if system == System::McCuneReischauer
+            && prev == Some(Some(Coda::N))
+            && initial.starts_with('g')
+        {
+            out.push('\'');
+        }
+
+        out.push_str(initial);
+        out.push_str(match system {
+            System::Revised => MEDIALS[x.medial as usize].0,
+            System::McCuneReischauer => MEDIALS[x.medial as usize].1,
+        });
+        if let Some(x) = x.coda {
+            out.push_str(coda(x));
+        }
+    }
+}
+
+/// 한글을 로마자로 옮김
+///
+/// 한글이 아닌 문자는 그대로 두고, 붙어 있는 한글은 한 단어로 보고 음운 변화를 적용함
+pub fn romanize_with(x: &str, system: System) -> String {
+    let mut res = String::new();
+    let mut syllables = Vec::new();
+
+    for c in x.chars() {
+        match decompose(c) {
+            Some(x) => syllables.push(x),
+            None => {
+                word(&mut syllables, system, &mut res);
+                syllables.clear();
+                res.push(c);
+            }
+        }
+    }
+    word(&mut syllables, system, &mut res);
+
+    res
+}
+
+/// `흰여울` → `huinyeoul`
+pub fn romanize(x: &str) -> String {
+    romanize_with(x, System::Revised)
+}
+
+#[test]
+fn test_romanize() {
+    assert_eq!(romanize("흰여울"), "huinyeoul");
+    assert_eq!(romanize("한국어"), "hangugeo");
+    assert_eq!(romanize("신라"), "silla");
+    assert_eq!(romanize("설날"), "seollal");
+    assert_eq!(romanize("종로"), "jongno");
+    assert_eq!(romanize("백마"), "baengma");
+    assert_eq!(romanize("독립"), "dongnip");
+    assert_eq!(romanize("같이"), "gachi");
+    assert_eq!(romanize("좋고"), "joko");
+    assert_eq!(romanize("닭"), "dak");
+    assert_eq!(romanize("읽어"), "ilgeo");
+    assert_eq!(
+        romanize("전문가가 되었습니다! 4"),
+        "jeonmungaga doeeotseumnida! 4"
+    );
+    assert_eq!(romanize("포켓몬 SV"), "pokenmon SV");
+}
+
+#[test]
+fn test_mccune_reischauer() {
+    let mr = |x| romanize_with(x, System::McCuneReischauer);
+
+    assert_eq!(mr("흰여울"), "hŭinyŏul");
+    assert_eq!(mr("한국어"), "han'gugŏ");
+    assert_eq!(mr("부산"), "pusan");
+    assert_eq!(mr("신라"), "shilla");
+    assert_eq!(mr("독립"), "tongnip");
+}
@@ -0,0 +1,552 @@
+use std::ops::Range;
+
+use rkiwi::{POSTag, analyzed::Token};
+use widestring::{U16String, u16str};
+
+use crate::{bracket::Bracket, episode, is_korean, is_pipe, tag};
+
+/// 언어 경계가 아닌 곳에서 자르는 것을 막기 위한 값
+///
+/// `설이벗방TV♥` `개인PT♥` 처럼 공백 없이 붙어 있는 토큰 사이는 자르지 않음
+const GLUE_PENALTY: f32 = 4.0;
+
+/// 괄호나 부제목으로 감싸진 토큰 묶음의 가중치
+///
+/// 감싸진 부분에만 나타나는 언어는 가중치를 주지 않음 (`비밀 - White Lie & Dark Truth -`)
+const ENCLOSED_WEIGHT: f32 = 0.5;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Class {
+    Korean,
+    Foreign,
+    /// 숫자, 기호, 에피소드 표기처럼 어느 쪽에도 붙을 수 있는 것
+    Neutral,
+    /// 값은 구분자에서 자를 때 더해지는 점수
+    Separator(f32),
+}
+
+#[derive(Debug, Clone)]
+pub struct Unit {
+    pub class: Class,
+    /// 토큰 위치
+    pub tokens: Range<usize>,
+    /// 원문 위치 (utf-16)
+    pub span: Range<usize>,
+    /// 괄호나 부제목으로 감싸진 토큰 묶음
+    pub enclosed: bool,
+}
+
+#[derive(Debug, Clone)]
+pub struct Segmentation {
+    /// 원문 순서대로 정렬된 언어 구간
+    pub runs: Vec<Run>,
+    pub score: f32,
+}
+
+impl Segmentation {
+    /// 한 언어에 속하는 구간들의 원문 위치 (utf-16)
+    pub fn spans(&self, side: Side) -> impl Iterator<Item = Range<usize>> + '_ {
+        self.runs
+            .iter()
+            .filter(move |x| x.side == side)
+            .map(|x| x.span.clone())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Side {
+    Foreign,
+    Korean,
+}
+
+/// 같은 언어가 이어지는 구간
+///
+/// 숫자, 기호, 괄호로 감싸진 부분은 앞쪽 구간에 붙음
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Run {
+    pub side: Side,
+    /// 단위 위치
+    pub units: Range<usize>,
+    /// 원문 위치 (utf-16)
+    pub span: Range<usize>,
+}
+
+pub fn end_of(token: &Token) -> usize {
+    token.chr_position + token.length
+}
+
+pub fn glued(a: &Token, b: &Token) -> bool {
+    end_of(a) == b.chr_position
+}
+
+fn is_foreign(pos_tag: &POSTag) -> bool {
+    *pos_tag == POSTag::SL || *pos_tag == POSTag::SH
+}
+
+pub fn is_word(pos_tag: &POSTag) -> bool {
+    is_korean(pos_tag) || is_foreign(pos_tag) || *pos_tag == POSTag::SN
+}
+
+/// `~부제목~` `-부제목-` 을 감싸는 기호인지 확인함
+///
+/// `Onee-chan`, `1~13` 처럼 양쪽이 공백 없이 단어와 붙어 있는 경우는 제외함
+fn is_subtitle_delimiter(xs: &[(U16String, Token)], i: usize) -> bool {
+    let (_, token) = &xs[i];
+
+    if token.tag != POSTag::SO {
+        return false;
+    }
+
+    let glued_word = |j: Option<usize>| {
+        j.and_then(|j| xs.get(j))
+            .is_some_and(|(_, x)| is_word(&x.tag) && (glued(x, token) || glued(token, x)))
+    };
+
+    !(glued_word(i.checked_sub(1)) && glued_word(Some(i + 1)))
+}
+
+/// 같은 기호끼리 짝지음
+///
+/// 짝을 찾으면 그 사이에서 열린 채로 남은 기호는 버림
+pub fn find_subtitle(xs: &[(U16String, Token)]) -> Vec<(usize, usize)> {
+    let mut res = Vec::new();
+
+    let mut open: Vec<usize> = Vec::new();
+
+    for i in (0..xs.len()).filter(|i| is_subtitle_delimiter(xs, *i)) {
+        let form = &xs[i].0;
+
+        match open.iter().rposition(|j| xs[*j].0 == *form) {
+            Some(at) => {
+                res.push((open[at], i));
+                open.truncate(at);
+            }
+            None => open.push(i),
+        }
+    }
+
+    res.sort();
+
+    res
+}
+
+fn classify(xs: &[(U16String, Token)], i: usize) -> Class {
+    let (form, token) = &xs[i];
+
+    let glued_prev = i > 0 && glued(&xs[i - 1].1, token);
+    let glued_next = xs.get(i + 1).is_some_and(|(_, x)| glued(token, x));
+
+    let separator = if is_pipe(form) {
+        Some(4.0)
+    }
+    // `Oyako SEX l 그!` 처럼 l을 파이프 대신 사용한 경우
+    else if form == u16str!("l") && !glued_prev && !glued_next {
+        Some(3.0)
+    }
+    // `2019/02` `Pokapoka+후일담추가` 는 구분자가 아님
+    else if glued_prev && glued_next {
+        None
+    } else if form == u16str!("/") || form == u16str!("／") {
+        Some(3.0)
+    } else if form == u16str!("+") || form == u16str!("＋") {
+        Some(1.0)
+    } else {
+        None
+    };
+
+    if let Some(bonus) = separator {
+        Class::Separator(bonus)
+    } else if is_korean(&token.tag) {
+        Class::Korean
+    } else if is_foreign(&token.tag) {
+        Class::Foreign
+    } else {
+        Class::Neutral
+    }
+}
+
+fn span_of(xs: &[(U16String, Token)], tokens: &Range<usize>) -> Range<usize> {
+    xs[tokens.start].1.chr_position..end_of(&xs[tokens.end - 1].1)
+}
+
+/// 토큰을 점수 계산의 단위로 묶음
+///
+/// 괄호나 부제목으로 감싸진 부분은 하나로 묶이며, 안쪽에서 더 많이 나타나는 언어로 분류됨
+pub fn units(xs: &[(U16String, Token)], brackets: &[Bracket]) -> Vec<Unit> {
+    let subtitles = find_subtitle(xs);
+
+    let mut res = Vec::new();
+    let mut i = 0;
+
+    while i < xs.len() {
+        let bracket = brackets
+            .iter()
+            .filter(|x| x.is_matched() && x.open == Some(i))
+            .max_by_key(|x| x.close);
+        let subtitle = subtitles.iter().find(|(s, _)| *s == i);
+
+        let end = match (bracket.and_then(|x| x.close), subtitle.map(|(_, e)| *e)) {
+            (Some(a), Some(b)) => Some(a.max(b)),
+            (a, b) => a.or(b),
+        };
+
+        if let Some(end) = end {
+            let tokens = i..end + 1;
+
+            let tagged = bracket.is_some() && tag::parse(&inner_text(xs, i + 1..end)).is_some();
+            // `(Zenpen)` `(상)` 처럼 괄호 안이 에피소드 표기뿐인 경우
+            let episode = episode::at(xs, i + 1).is_some_and(|x| x.next == end);
+
+            let (ko, fr) = xs[i + 1..end].iter().fold((0, 0), |(ko, fr), (_, t)| {
+                if is_korean(&t.tag) {
+                    (ko + 1, fr)
+                } else if is_foreign(&t.tag) {
+                    (ko, fr + 1)
+                } else {
+                    (ko, fr)
+                }
+            });
+
+            let class = if tagged || episode || (ko == 0 && fr == 0) {
+                Class::Neutral
+            } else if ko >= fr {
+                Class::Korean
+            } else {
+                Class::Foreign
+            };
+
+            res.push(Unit {
+                class,
+                span: span_of(xs, &tokens),
+                tokens,
+                enclosed: true,
+            });
+
+            i = end + 1;
+            continue;
+        }
+
+        // `Vol.05` `Ch. 1` `Part 2` `Dainiwa` `Kouhen` `#2` 같은 에피소드 표기
+        if let Some(found) = episode::at(xs, i) {
+            let end = found.next;
+            let tokens = i..end;
+            res.push(Unit {
+                class: Class::Neutral,
+                span: span_of(xs, &tokens),
+                tokens,
+                enclosed: false,
+            });
+
+            i = end;
+            continue;
+        }
+
+        let tokens = i..i + 1;
+        res.push(Unit {
+            class: classify(xs, i),
+            span: span_of(xs, &tokens),
+            tokens,
+            enclosed: false,
+        });
+
+        i += 1;
+    }
+
+    res
+}
+
+fn inner_text(xs: &[(U16String, Token)], tokens: Range<usize>) -> String {
+    let mut res = String::new();
+
+    for (i, (form, token)) in xs.iter().enumerate().take(tokens.end).skip(tokens.start) {
+        if i > tokens.start && !glued(&xs[i - 1].1, token) {
+            res.push(' ');
+        }
+        res.push_str(&form.to_string_lossy());
+    }
+
+    res
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Cut {
+    score: f32,
+    /// 왼쪽 부분의 끝 (단위 위치)
+    left_end: usize,
+    /// 오른쪽 부분의 시작 (단위 위치), 구분자에서 자르면 구분자는 양쪽 어디에도 속하지 않음
+    right_start: usize,
+}
+
+/// 모든 자를 수 있는 위치에 점수를 매겨 가장 높은 곳에서 자름
+///
+/// 왼쪽이 외국어이고 오른쪽이 한국어인 경우와 그 반대 경우를 똑같이 계산함.
+/// 점수는 양쪽에 맞는 언어의 단위가 많을수록 높아지고, 구분자에서 자르면 더해지며,
+/// 공백 없이 붙어 있는 곳에서 자르면 깎임.
+/// 점수가 같으면 뒤쪽 위치를 선택하므로, 숫자나 기호는 앞쪽 제목에 붙음 (`이야기 1~13 Miyu-chan`)
+///
+/// 양 끝에서 자르는 경우는 한 언어만 있는 것으로 봄
+pub fn segment(xs: &[(U16String, Token)], brackets: &[Bracket]) -> Segmentation {
+    let units = units(xs, brackets);
+
+    let strong = |class: Class| units.iter().any(|x| x.class == class && !x.enclosed);
+    let has_strong_korean = strong(Class::Korean);
+    let has_strong_foreign = strong(Class::Foreign);
+
+    let weight = |unit: &Unit| {
+        let has_strong = match unit.class {
+            Class::Korean => has_strong_korean,
+            Class::Foreign => has_strong_foreign,
+            _ => return 0.0,
+        };
+
+        if !unit.enclosed {
+            1.0
+        } else if has_strong {
+            ENCLOSED_WEIGHT
+        } else {
+            0.0
+        }
+    };
+
+    let mut best: Option<(Class, Cut)> = None;
+
+    // 점수가 같으면 한국어를 앞에 두는 쪽을 선택함
+    for first in [Class::Korean, Class::Foreign] {
+        // 왼쪽에 있어야 하는 언어는 양수, 오른쪽에 있어야 하는 언어는 음수
+        let mut prefix = vec![0.0f32; units.len() + 1];
+        for (i, unit) in units.iter().enumerate() {
+            let w = weight(unit);
+            let w = if unit.class == first { w } else { -w };
+            prefix[i + 1] = prefix[i] + w;
+        }
+        let total = prefix[units.len()];
+
+        let plain = (0..=units.len()).map(|c| {
+            let glue = c > 0
+                && c < units.len()
+                && glued(
+                    &xs[units[c - 1].tokens.end - 1].1,
+                    &xs[units[c].tokens.start].1,
+                );
+
+            Cut {
+                score: 2.0 * prefix[c] - total - if glue { GLUE_PENALTY } else { 0.0 },
+                left_end: c,
+                right_start: c,
+            }
+        });
+
+        let separated = units
+            .iter()
+            .enumerate()
+            .filter_map(|(s, unit)| match unit.class {
+                Class::Separator(bonus) => Some(Cut {
+                    score: 2.0 * prefix[s] - total + bonus,
+                    left_end: s,
+                    right_start: s + 1,
+                }),
+                _ => None,
+            });
+
+        for cut in plain.chain(separated) {
+            let better = match &best {
+                None => true,
+                Some((best_first, best)) => {
+                    cut.score > best.score
+                        || (cut.score == best.score
+                            && *best_first == first
+                            && cut.left_end + cut.right_start > best.left_end + best.right_start)
+                }
+            };
+
+            if better {
+                best = Some((first, cut));
+            }
+        }
+    }
+
+    let Some((first, cut)) = best else {
+        return Segmentation {
+            runs: Vec::new(),
+            score: 0.0,
+        };
+    };
+
+    let (first, second) = if first == Class::Foreign {
+        (Side::Foreign, Side::Korean)
+    } else {
+        (Side::Korean, Side::Foreign)
+    };
+
+    // 숫자나 기호만 남은 쪽은 반대쪽에 붙임 (`1 한국어 제목`)
+    let has_language = |units: &[Unit]| {
+        units
+            .iter()
+            .any(|x| x.class == Class::Korean || x.class == Class::Foreign)
+    };
+
+    // 구분자 없이 약어만 남은 쪽도 반대쪽에 붙임 (`철혈 M16`)
+    let separated = cut.left_end != cut.right_start;
+    let is_weak =
+        |units: &[Unit]| !has_language(units) || (!separated && is_abbreviation(xs, units));
+
+    let runs = if is_weak(&units[..cut.left_end]) {
+        runs(xs, &units, 0..units.len(), second)
+    } else if is_weak(&units[cut.right_start..]) {
+        runs(xs, &units, 0..units.len(), first)
+    } else {
+        let mut res = runs(xs, &units, 0..cut.left_end, first);
+        res.extend(runs(xs, &units, cut.right_start..units.len(), second));
+        res
+    };
+
+    Segmentation {
+        runs,
+        score: cut.score,
+    }
+}
+
+fn side_of(unit: &Unit) -> Option<Side> {
+    match unit.class {
+        Class::Korean if !unit.enclosed => Some(Side::Korean),
+        Class::Foreign if !unit.enclosed => Some(Side::Foreign),
+        _ => None,
+    }
+}
+
+/// `M16` `SV MTR` 처럼 짧은 약어로만 이루어진 구간
+fn is_abbreviation(xs: &[(U16String, Token)], units: &[Unit]) -> bool {
+    units
+        .iter()
+        .filter(|x| side_of(x).is_some())
+        .flat_map(|x| xs[x.tokens.clone()].iter())
+        .all(|(form, _)| {
+            let form = form.to_string_lossy();
+            form.chars().count() <= 4
+                && form
+                    .chars()
+                    .all(|c| c.is_ascii_uppercase() || c.is_ascii_digit())
+        })
+}
+
+/// 한쪽 부분을 언어 구간으로 나눔
+///
+/// 반대 언어의 구간은 앞뒤로 이 쪽의 언어에 둘러싸여 있거나, 공백 없이 붙어 있거나,
+/// 약어일 경우 이 쪽의 언어로 봄 (`네게브 x 카98k`, `포켓몬 SV MTR 6-7`)
+fn runs(xs: &[(U16String, Token)], units: &[Unit], range: Range<usize>, side: Side) -> Vec<Run> {
+    let mut groups: Vec<(Option<Side>, Range<usize>)> = Vec::new();
+
+    for i in range {
+        match (side_of(&units[i]), groups.last_mut()) {
+            (Some(a), Some((Some(b), group))) if a == *b => group.end = i + 1,
+            // 앞쪽 구간이 없는 숫자나 기호는 뒤쪽 구간에 붙임
+            (Some(a), Some((b @ None, group))) => {
+                *b = Some(a);
+                group.end = i + 1;
+            }
+            (None, Some((_, group))) => group.end = i + 1,
+            (x, _) => groups.push((x, i..i + 1)),
+        }
+    }
+
+    let glued_units = |a: &Unit, b: &Unit| glued(&xs[a.tokens.end - 1].1, &xs[b.tokens.start].1);
+
+    let absorbed = (0..groups.len())
+        .map(|i| {
+            let (x, group) = &groups[i];
+            if *x == Some(side) || x.is_none() {
+                return true;
+            }
+
+            let prev = i.checked_sub(1).map(|i| &groups[i]);
+            let next = groups.get(i + 1);
+
+            let sandwiched = prev.is_some() && next.is_some();
+            let glued_prev =
+                prev.is_some_and(|(_, p)| glued_units(&units[p.end - 1], &units[group.start]));
+            let glued_next =
+                next.is_some_and(|(_, n)| glued_units(&units[group.end - 1], &units[n.start]));
+
+            sandwiched || glued_prev || glued_next || is_abbreviation(xs, &units[group.clone()])
+        })
+        .collect::<Vec<_>>();
+
+    let mut res: Vec<Run> = Vec::new();
+
+    for ((x, group), absorbed) in groups.into_iter().zip(absorbed) {
+        let x = if absorbed { side } else { x.unwrap_or(side) };
+
+        match res.last_mut() {
+            Some(run) if run.side == x => run.units.end = group.end,
+            _ => res.push(Run {
+                side: x,
+                units: group,
+                span: 0..0,
+            }),
+        }
+    }
+
+    for run in &mut res {
+        run.span = units[run.units.start].span.start..units[run.units.end - 1].span.end;
+    }
+
+    res
+}
+
+#[test]
+fn test_find_subtitle() -> Result<(), Box<dyn std::error::Error>> {
+    use rkiwi::{KiwiBuilder, Match};
+
+    let kiwi = KiwiBuilder::new(None, Default::default())?.build(None, None)?;
+
+    let txt = U16String::from_str(
+        "비밀의 버스 투어 ~나의 버스 가이드 일지~ [korean} Himitsu no Bus Tour ~Boku no Bus Guide Nisshi~",
+    );
+    let match_options = Match::new().all_with_normailize_coda();
+    let analyzed = kiwi.analyze_w(&txt, 1, match_options, None, None)?;
+    let xs = analyzed.to_vec_w();
+    let res = find_subtitle(&xs);
+
+    fn start(xs: &[(U16String, Token)], res: &[(usize, usize)], i: usize) -> usize {
+        let token = xs[res[i].0].1;
+        token.chr_position
+    }
+
+    fn end(xs: &[(U16String, Token)], res: &[(usize, usize)], i: usize) -> usize {
+        let token = xs[res[i].1].1;
+        token.chr_position + token.length
+    }
+
+    assert_eq!(
+        &txt[start(&xs, &res, 0)..end(&xs, &res, 0)].to_string()?,
+        "~나의 버스 가이드 일지~"
+    );
+    assert_eq!(
+        &txt[start(&xs, &res, 1)..end(&xs, &res, 1)].to_string()?,
+        "~Boku no Bus Guide Nisshi~"
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_find_subtitle_skips_hyphen_and_range() -> Result<(), Box<dyn std::error::Error>> {
+    use rkiwi::{KiwiBuilder, Match};
+
+    let kiwi = KiwiBuilder::new(None, Default::default())?.build(None, None)?;
+
+    let txt = U16String::from_str("Onee-chan 1~13 -유성에서 온 번식자-｜Hanshoku-sha");
+    let match_options = Match::new().all_with_normailize_coda();
+    let xs = kiwi
+        .analyze_w(&txt, 1, match_options, None, None)?
+        .to_vec_w();
+    let res = find_subtitle(&xs);
+
+    assert_eq!(res.len(), 1);
+    assert_eq!(
+        &txt[span_of(&xs, &(res[0].0..res[0].1 + 1))].to_string()?,
+        "-유성에서 온 번식자-"
+    );
+
+    Ok(())
+}
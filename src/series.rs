@@ -0,0 +1,316 @@
+use std::collections::HashMap;
+
+use serde_json::{Value, json};
+
+use crate::{Split, episode::Episode, normalize::normalize, segment::Side};
+
+/// 시리즈에 속한 제목
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Member {
+    /// 입력에서의 위치
+    pub index: usize,
+    pub title: String,
+    pub foreign: String,
+    pub korean: String,
+    /// 에피소드를 지운 외국어 제목
+    pub foreign_base: String,
+    /// 에피소드를 지운 한국어 제목
+    pub korean_base: String,
+    /// (시작, 끝)
+    pub episode: Option<(u32, u32)>,
+    /// 에피소드 원문 (`4` `Vol.05`)
+    pub episode_text: Option<String>,
+}
+
+/// 같은 시리즈의 다른 제목으로부터 만든 제목
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Derived {
+    /// 입력에서의 위치
+    pub index: usize,
+    /// 만들어진 제목의 언어
+    pub side: Side,
+    pub title: String,
+    /// 근거가 된 제목들
+    pub sources: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Series {
+    /// 에피소드를 지운 외국어 제목
+    pub foreign: String,
+    /// 에피소드를 지운 한국어 제목
+    pub korean: String,
+    /// 에피소드 순으로 정렬됨, 에피소드가 없는 것이 먼저 옴
+    pub members: Vec<Member>,
+    /// `fill_missing`으로 채워짐
+    pub derived: Vec<Derived>,
+}
+
+/// 제목 끝에 남을 수 있는 구분 문자
+const TRAILING_CHARS: [char; 9] = [' ', '-', '~', '～', ':', '|', '/', ',', '.'];
+
+/// 에피소드 표기를 지운 제목
+pub fn base_title(title: &str, episodes: &[Episode], side: Side) -> String {
+    let mut res = title.to_owned();
+
+    for x in episodes.iter().filter(|x| x.side == side) {
+        if let Some(at) = res.rfind(&x.text) {
+            res.replace_range(at..at + x.text.len(), "");
+        }
+    }
+
+    res.split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .trim_end_matches(TRAILING_CHARS)
+        .to_owned()
+}
+
+pub fn root(parent: &mut [usize], i: usize) -> usize {
+    let mut i = i;
+
+    while parent[i] != i {
+        parent[i] = parent[parent[i]];
+        i = parent[i];
+    }
+
+    i
+}
+
+/// 에피소드, 태그, 장식 문자를 지운 제목이 같으면 같은 시리즈로 묶음
+///
+/// 외국어나 한국어 중 한쪽만 같아도 묶이므로, 한쪽 언어만 있는 제목도 양쪽 언어가 있는 제목을 통해 묶일 수 있음
+pub fn group(items: &[(String, Split)]) -> Vec<Series> {
+    let bases = items
+        .iter()
+        .map(|(_, x)| {
+            (
+                base_title(&x.foreign, &x.episodes, Side::Foreign),
+                base_title(&x.korean, &x.episodes, Side::Korean),
+            )
+        })
+        .collect::<Vec<_>>();
+
+    let mut parent = (0..items.len()).collect::<Vec<_>>();
+    let mut seen = HashMap::new();
+
+    for (i, (foreign, korean)) in bases.iter().enumerate() {
+        for (side, base) in [(Side::Foreign, foreign), (Side::Korean, korean)] {
+            let key = normalize(base);
+            if key.is_empty() {
+                continue;
+            }
+
+            let first = *seen.entry((side, key)).or_insert(i);
+            let (a, b) = (root(&mut parent, first), root(&mut parent, i));
+            parent[b] = a;
+        }
+    }
+
+    // 먼저 나온 제목의 순서대로 시리즈를 만듦
+    let mut res: Vec<Series> = Vec::new();
+    let mut at = HashMap::new();
+
+    for (i, (title, split)) in items.iter().enumerate() {
+        let r = root(&mut parent, i);
+        let (foreign, korean) = &bases[i];
+
+        let series = *at.entry(r).or_insert_with(|| {
+            res.push(Series {
+                foreign: String::new(),
+                korean: String::new(),
+                members: Vec::new(),
+                derived: Vec::new(),
+            });
+            res.len() - 1
+        });
+        let series = &mut res[series];
+
+        if series.foreign.is_empty() {
+            series.foreign = foreign.clone();
+        }
+        if series.korean.is_empty() {
+            series.korean = korean.clone();
+        }
+
+        let episode = split.episodes.first();
+
+        series.members.push(Member {
+            index: i,
+            title: title.clone(),
+            foreign: split.foreign.clone(),
+            korean: split.korean.clone(),
+            foreign_base: foreign.clone(),
+            korean_base: korean.clone(),
+            episode: episode.map(|x| (x.start, x.end)),
+            episode_text: episode.map(|x| x.text.clone()),
+        });
+    }
+
+    for series in &mut res {
+        series.members.sort_by_key(|x| (x.episode, x.index));
+    }
+
+    res
+}
+
+/// 한쪽 언어 제목이 없는 것에 같은 시리즈의 다른 제목으로부터 제목을 만들어 줌
+///
+/// 가장 많이 쓰인 제목에 빠진 쪽의 에피소드를 다시 붙임
+pub fn fill_missing(series: &mut [Series]) {
+    for x in series {
+        let mut derived = Vec::new();
+
+        for side in [Side::Foreign, Side::Korean] {
+            let title_of = |m: &Member| match side {
+                Side::Foreign => m.foreign.clone(),
+                Side::Korean => m.korean.clone(),
+            };
+            let base_of = |m: &Member| match side {
+                Side::Foreign => m.foreign_base.clone(),
+                Side::Korean => m.korean_base.clone(),
+            };
+
+            // 정규화한 제목마다 처음 쓰인 제목과 근거가 된 제목들
+            let mut bases: Vec<(String, String, Vec<String>)> = Vec::new();
+            for m in x.members.iter().filter(|m| !base_of(m).is_empty()) {
+                let key = normalize(&base_of(m));
+                match bases.iter_mut().find(|(k, _, _)| *k == key) {
+                    Some((_, _, sources)) => sources.push(m.title.clone()),
+                    None => bases.push((key, base_of(m), vec![m.title.clone()])),
+                }
+            }
+
+            // 같으면 먼저 나온 것
+            let Some((_, base, sources)) = bases
+                .iter()
+                .rev()
+                .max_by_key(|(_, _, sources)| sources.len())
+            else {
+                continue;
+            };
+
+            for m in x.members.iter().filter(|m| title_of(m).is_empty()) {
+                let title = match &m.episode_text {
+                    Some(episode) => format!("{base} {episode}"),
+                    None => base.clone(),
+                };
+
+                derived.push(Derived {
+                    index: m.index,
+                    side,
+                    title,
+                    sources: sources.clone(),
+                });
+            }
+        }
+
+        x.derived = derived;
+    }
+}
+
+pub fn to_json(series: &[Series]) -> Value {
+    series
+        .iter()
+        .map(|x| {
+            json!({
+                "foreign": x.foreign,
+                "korean": x.korean,
+                "members": x.members.iter().map(|m| json!({
+                    "index": m.index,
+                    "title": m.title,
+                    "foreign": m.foreign,
+                    "korean": m.korean,
+                    "episode": m.episode.map(|(start, end)| json!({ "start": start, "end": end })),
+                })).collect::<Vec<_>>(),
+                "derived": x.derived.iter().map(|d| json!({
+                    "index": d.index,
+                    "side": match d.side {
+                        Side::Foreign => "foreign",
+                        Side::Korean => "korean",
+                    },
+                    "title": d.title,
+                    "derived": true,
+                    "sources": d.sources,
+                })).collect::<Vec<_>>(),
+            })
+        })
+        .collect()
+}
+
+#[test]
+fn test_group() -> Result<(), Box<dyn std::error::Error>> {
+    use rkiwi::KiwiBuilder;
+
+    let kiwi = KiwiBuilder::new(None, Default::default())?.build(None, None)?;
+
+    let items = [
+        "Expert ni Narimashita! 5",
+        "Ja Ja Ja Ja Japan 3",
+        "Expert ni Narimashita! 4 | 전문가가 되었습니다! 4",
+        "전문가가 되었습니다! 3",
+        "Ja Ja Ja Ja Japan 2 ♥",
+        "Senko & Shiro X Horse",
+    ]
+    .into_iter()
+    .map(|x| Ok((x.to_owned(), crate::process(&kiwi, x)?)))
+    .collect::<Result<Vec<_>, Box<dyn std::error::Error>>>()?;
+
+    let res = group(&items);
+    assert_eq!(res.len(), 3);
+
+    assert_eq!(res[0].foreign, "Expert ni Narimashita!");
+    assert_eq!(res[0].korean, "전문가가 되었습니다!");
+    let episodes = res[0].members.iter().map(|x| x.episode).collect::<Vec<_>>();
+    assert_eq!(episodes, [Some((3, 3)), Some((4, 4)), Some((5, 5))]);
+
+    assert_eq!(res[1].foreign, "Ja Ja Ja Ja Japan");
+    assert_eq!(res[1].members.len(), 2);
+    assert_eq!(res[1].members[0].title, "Ja Ja Ja Ja Japan 2 ♥");
+
+    assert_eq!(res[2].members.len(), 1);
+
+    Ok(())
+}
+
+#[test]
+fn test_fill_missing() -> Result<(), Box<dyn std::error::Error>> {
+    use rkiwi::KiwiBuilder;
+
+    let kiwi = KiwiBuilder::new(None, Default::default())?.build(None, None)?;
+
+    let items = [
+        "Expert ni Narimashita! 3 | 전문가가 되었습니다! 3",
+        "Expert ni Narimashita! 4",
+        "Expert ni Narimashita! 5 | 전문가가 되었습니다! 5",
+        "전문가가 되었습니다! 6",
+    ]
+    .into_iter()
+    .map(|x| Ok((x.to_owned(), crate::process(&kiwi, x)?)))
+    .collect::<Result<Vec<_>, Box<dyn std::error::Error>>>()?;
+
+    let mut res = group(&items);
+    fill_missing(&mut res);
+    assert_eq!(res.len(), 1);
+
+    let derived = &res[0].derived;
+    assert_eq!(derived.len(), 2);
+
+    assert_eq!(derived[0].side, Side::Foreign);
+    assert_eq!(derived[0].index, 3);
+    assert_eq!(derived[0].title, "Expert ni Narimashita! 6");
+
+    assert_eq!(derived[1].side, Side::Korean);
+    assert_eq!(derived[1].index, 1);
+    assert_eq!(derived[1].title, "전문가가 되었습니다! 4");
+    assert_eq!(
+        derived[1].sources,
+        [
+            "Expert ni Narimashita! 3 | 전문가가 되었습니다! 3",
+            "Expert ni Narimashita! 5 | 전문가가 되었습니다! 5",
+            "전문가가 되었습니다! 6",
+        ]
+    );
+
+    Ok(())
+}
@@ -0,0 +1,330 @@
+use std::ops::Range;
+
+use rkiwi::{POSTag, analyzed::Token};
+use widestring::{U16Str, U16String};
+
+use crate::{
+    bracket::Bracket,
+    segment::{end_of, glued},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Censorship {
+    /// `(uncensored)` `(무수정)` 원래 검열되지 않은 작품
+    Uncensored,
+    /// `(decensored)` 검열을 지운 작품
+    Decensored,
+    /// `(censored)`
+    Censored,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TagKind {
+    /// 번역된 언어, ISO 639-1 언어 코드를 가짐 (`ko` `en`)
+    Language(&'static str),
+    Censorship(Censorship),
+    /// `(Colorized)` `[풀컬러]`
+    Colorized,
+    /// `[Digital]` `[DL版]`
+    Digital,
+    /// `[Textless]` `[無字]`
+    Textless,
+    /// `#NTR` 처럼 괄호 없이 쓰인 해시태그, `#`를 뺀 원문을 가짐
+    ///
+    /// `#2` 처럼 숫자만 있는 것은 에피소드로 봄
+    Hashtag(String),
+}
+
+/// 괄호로 감싸진 배포 태그 (`[Korean]` `(decensored)`)와 해시태그
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Tag {
+    pub kind: TagKind,
+    /// 괄호를 포함한 원문
+    pub text: String,
+    /// 괄호를 포함한 원문 위치 (utf-16)
+    pub span: Range<usize>,
+}
+
+/// 언어 표기와 ISO 639-1 언어 코드
+const LANGUAGES: [(&str, &str); 40] = [
+    ("korean", "ko"),
+    ("kor", "ko"),
+    ("kr", "ko"),
+    ("ko", "ko"),
+    ("한국어", "ko"),
+    ("한글", "ko"),
+    ("한국어 번역", "ko"),
+    ("韓国語", "ko"),
+    ("韓国翻訳", "ko"),
+    ("韓國語", "ko"),
+    ("韓國翻譯", "ko"),
+    ("english", "en"),
+    ("eng", "en"),
+    ("en", "en"),
+    ("영어", "en"),
+    ("英語", "en"),
+    ("英訳", "en"),
+    ("英語翻訳", "en"),
+    ("japanese", "ja"),
+    ("jpn", "ja"),
+    ("jp", "ja"),
+    ("ja", "ja"),
+    ("일본어", "ja"),
+    ("日本語", "ja"),
+    ("chinese", "zh"),
+    ("chn", "zh"),
+    ("cn", "zh"),
+    ("zh", "zh"),
+    ("중국어", "zh"),
+    ("中国語", "zh"),
+    ("中国翻訳", "zh"),
+    ("中國翻譯", "zh"),
+    ("中文", "zh"),
+    ("汉化", "zh"),
+    ("漢化", "zh"),
+    ("spanish", "es"),
+    ("español", "es"),
+    ("french", "fr"),
+    ("russian", "ru"),
+    ("vietnamese", "vi"),
+];
+
+/// `Korean Translated` 처럼 언어 뒤에 붙는 말
+const TRANSLATED: [&str; 4] = [" translated", " translation", " trans", " ver"];
+
+/// 언어 표기 양쪽에 남아 있을 수 있는 괄호 문자
+const BRACKET_CHARS: &str = "()[]{}<>（）［］｛｝＜＞〈〉《》「」｢｣『』【】〔〕";
+
+const CENSORSHIP: [(&str, Censorship); 9] = [
+    ("uncensored", Censorship::Uncensored),
+    ("무수정", Censorship::Uncensored),
+    ("無修正", Censorship::Uncensored),
+    ("decensored", Censorship::Decensored),
+    ("decen", Censorship::Decensored),
+    ("무검열", Censorship::Decensored),
+    ("탈검열", Censorship::Decensored),
+    ("censored", Censorship::Censored),
+    ("검열", Censorship::Censored),
+];
+
+const FLAGS: [(&str, TagKind); 13] = [
+    ("colorized", TagKind::Colorized),
+    ("colorised", TagKind::Colorized),
+    ("full color", TagKind::Colorized),
+    ("컬러", TagKind::Colorized),
+    ("컬러화", TagKind::Colorized),
+    ("풀컬러", TagKind::Colorized),
+    ("カラー化", TagKind::Colorized),
+    ("digital", TagKind::Digital),
+    ("dl版", TagKind::Digital),
+    ("디지털", TagKind::Digital),
+    ("textless", TagKind::Textless),
+    ("無字", TagKind::Textless),
+    ("식자 없음", TagKind::Textless),
+];
+
+fn normalize(x: &str) -> String {
+    x.split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .to_lowercase()
+}
+
+/// 언어 표기를 언어 코드로 바꿈
+///
+/// 대소문자와 양쪽에 남은 괄호는 무시함 (`[korean}` `Korean]` `[한국어]`)
+pub fn language(x: &str) -> Option<&'static str> {
+    let x = normalize(x.trim_matches(|c: char| c.is_whitespace() || BRACKET_CHARS.contains(c)));
+
+    let find = |x: &str| {
+        LANGUAGES
+            .iter()
+            .find(|(word, _)| *word == x)
+            .map(|(_, code)| *code)
+    };
+
+    find(&x).or_else(|| {
+        TRANSLATED
+            .iter()
+            .find_map(|suffix| x.strip_suffix(suffix))
+            .and_then(find)
+    })
+}
+
+/// 괄호 안쪽 문자열을 태그로 바꿈
+pub fn parse(x: &str) -> Option<TagKind> {
+    let x = normalize(x);
+
+    if let Some(code) = language(&x) {
+        return Some(TagKind::Language(code));
+    }
+
+    CENSORSHIP
+        .iter()
+        .find(|(word, _)| *word == x)
+        .map(|(_, censorship)| TagKind::Censorship(*censorship))
+        .or_else(|| {
+            FLAGS
+                .iter()
+                .find(|(word, _)| *word == x)
+                .map(|(_, kind)| kind.clone())
+        })
+}
+
+/// `[Korean` `Korean]` 처럼 한쪽 괄호가 없는 언어 표기
+///
+/// 짝이 없는 괄호와 언어 표기의 토큰 위치, 언어 코드를 반환함
+pub fn broken_language_markers(
+    xs: &[(U16String, Token)],
+    brackets: &[Bracket],
+) -> Vec<(Range<usize>, &'static str)> {
+    brackets
+        .iter()
+        .filter(|x| !x.is_matched())
+        .filter_map(|x| {
+            let (tokens, word) = match (x.open, x.close) {
+                (Some(open), None) => (open..open + 2, open + 1),
+                (None, Some(close)) => (close.checked_sub(1)?..close + 1, close.checked_sub(1)?),
+                _ => return None,
+            };
+
+            let (form, token) = xs.get(word)?;
+            let glued = glued(&xs[tokens.start].1, &xs[tokens.start + 1].1);
+
+            (glued && token.tag == POSTag::SL)
+                .then(|| language(&form.to_string_lossy()))?
+                .map(|code| (tokens, code))
+        })
+        .collect()
+}
+
+/// 태그를 모두 찾음
+///
+/// `[korean}` 처럼 종류가 다른 괄호끼리 짝지어진 경우와 `[Korean` 처럼 한쪽 괄호가 없는 언어 표기도 포함함
+pub fn find(text: &U16Str, xs: &[(U16String, Token)], brackets: &[Bracket]) -> Vec<Tag> {
+    let matched = brackets.iter().filter(|x| x.is_matched()).filter_map(|x| {
+        let inner = text[x.span.start + 1..x.span.end - 1].to_string_lossy();
+
+        Some(Tag {
+            kind: parse(&inner)?,
+            text: text[x.span.clone()].to_string_lossy(),
+            span: x.span.clone(),
+        })
+    });
+
+    let broken = broken_language_markers(xs, brackets)
+        .into_iter()
+        .map(|(tokens, code)| {
+            let span = xs[tokens.start].1.chr_position..end_of(&xs[tokens.end - 1].1);
+
+            Tag {
+                kind: TagKind::Language(code),
+                text: text[span.clone()].to_string_lossy(),
+                span,
+            }
+        });
+
+    let hashtags = xs
+        .iter()
+        .filter(|(_, t)| t.tag == POSTag::W_HASHTAG)
+        .filter_map(|(form, token)| {
+            let form = form.to_string_lossy();
+            let name = form.trim_start_matches(['#', '＃']);
+
+            (!name.is_empty() && !name.chars().all(|c| c.is_ascii_digit())).then(|| Tag {
+                kind: TagKind::Hashtag(name.to_owned()),
+                text: form.clone(),
+                span: token.chr_position..end_of(token),
+            })
+        });
+
+    let mut res = matched.chain(broken).chain(hashtags).collect::<Vec<_>>();
+    res.sort_by_key(|x| x.span.start);
+
+    res
+}
+
+/// 태그로부터 모은 작품 정보
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Release {
+    /// ISO 639-1 언어 코드
+    pub language: Option<&'static str>,
+    pub censorship: Option<Censorship>,
+    pub colorized: bool,
+    pub digital: bool,
+    pub textless: bool,
+    /// `#`를 뺀 해시태그
+    pub hashtags: Vec<String>,
+    /// 원문 순서대로 정렬된 태그
+    pub tags: Vec<Tag>,
+}
+
+impl Release {
+    /// 같은 종류의 태그가 여러 개면 먼저 나온 것을 씀
+    pub fn new(tags: Vec<Tag>) -> Self {
+        let mut res = Self::default();
+
+        for tag in &tags {
+            match &tag.kind {
+                TagKind::Language(x) => {
+                    res.language.get_or_insert(x);
+                }
+                TagKind::Censorship(x) => {
+                    res.censorship.get_or_insert(*x);
+                }
+                TagKind::Colorized => res.colorized = true,
+                TagKind::Digital => res.digital = true,
+                TagKind::Textless => res.textless = true,
+                TagKind::Hashtag(x) => res.hashtags.push(x.clone()),
+            }
+        }
+
+        res.tags = tags;
+        res
+    }
+}
+
+#[test]
+fn test_find() -> Result<(), Box<dyn std::error::Error>> {
+    use rkiwi::{KiwiBuilder, Match};
+
+    let kiwi = KiwiBuilder::new(None, Default::default())?.build(None, None)?;
+    let match_options = Match::new().all_with_normailize_coda();
+
+    let txt =
+        U16String::from_str("토끼 구멍에 빠지다 (Blue Archive) [Korean} (Decensored) [Digital]");
+    let xs = kiwi
+        .analyze_w(&txt, 1, match_options, None, None)?
+        .to_vec_w();
+    let brackets = crate::bracket::match_brackets(&xs);
+
+    let release = Release::new(find(&txt, &xs, &brackets));
+    assert_eq!(release.tags.len(), 3);
+    assert_eq!(release.tags[0].text, "[Korean}");
+    assert_eq!(release.language, Some("ko"));
+    assert_eq!(release.censorship, Some(Censorship::Decensored));
+    assert!(release.digital && !release.colorized);
+
+    assert_eq!(parse(" Full  Color "), Some(TagKind::Colorized));
+    assert_eq!(parse("Blue Archive"), None);
+
+    assert_eq!(language("[한국어]"), Some("ko"));
+    assert_eq!(language("[中国翻訳]"), Some("zh"));
+    assert_eq!(language("English Translated"), Some("en"));
+    assert_eq!(language("JAPANESE"), Some("ja"));
+    assert_eq!(language("Korean}"), Some("ko"));
+    assert_eq!(language("Kore"), None);
+
+    // 한쪽 괄호가 없는 경우
+    let txt = U16String::from_str("Title [Korean");
+    let xs = kiwi
+        .analyze_w(&txt, 1, match_options, None, None)?
+        .to_vec_w();
+    let brackets = crate::bracket::match_brackets(&xs);
+    let tags = find(&txt, &xs, &brackets);
+    assert_eq!(tags.len(), 1);
+    assert_eq!(tags[0].text, "[Korean");
+    assert_eq!(tags[0].kind, TagKind::Language("ko"));
+
+    Ok(())
+}
@@ -0,0 +1,254 @@
+use linfa_preprocessing::countgrams::CountVectorizer;
+use ndarray::Array1;
+use rkiwi::{Kiwi, POSTag};
+use serde_json::{Value, json};
+
+use crate::{Split, align::content_morphemes};
+
+/// 외국어 단어에서 만드는 문자 n-gram의 길이
+const CHAR_NGRAM: usize = 3;
+
+/// 형태소 외에 단어로 쓰는 품사
+const EXTRA_TAGS: [POSTag; 3] = [POSTag::SL, POSTag::SH, POSTag::SN];
+
+/// 0이 아닌 값만 가진 희소 벡터, 길이는 1로 맞춰짐
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SparseVector {
+    /// 오름차순
+    pub indices: Vec<usize>,
+    pub values: Vec<f32>,
+}
+
+impl SparseVector {
+    /// 0인 값을 지우고 길이를 1로 맞춤
+    pub fn from_dense(x: &[f32]) -> Self {
+        let mut res = Self::default();
+        for (i, x) in x.iter().enumerate().filter(|(_, x)| **x != 0.0) {
+            res.indices.push(i);
+            res.values.push(*x);
+        }
+        res.normalize();
+
+        res
+    }
+
+    /// 길이를 1로 맞춤
+    fn normalize(&mut self) {
+        let norm = self.values.iter().map(|x| x * x).sum::<f32>().sqrt();
+        if norm > 0.0 {
+            self.values.iter_mut().for_each(|x| *x /= norm);
+        }
+    }
+
+    /// 길이가 1이므로 코사인 유사도와 같음
+    pub fn dot(&self, other: &Self) -> f32 {
+        let (mut i, mut j) = (0, 0);
+        let mut res = 0.0;
+
+        while i < self.indices.len() && j < other.indices.len() {
+            match self.indices[i].cmp(&other.indices[j]) {
+                std::cmp::Ordering::Less => i += 1,
+                std::cmp::Ordering::Greater => j += 1,
+                std::cmp::Ordering::Equal => {
+                    res += self.values[i] * other.values[j];
+                    i += 1;
+                    j += 1;
+                }
+            }
+        }
+
+        res
+    }
+
+    pub fn to_json(&self) -> Value {
+        json!({ "indices": self.indices, "values": self.values })
+    }
+
+    pub fn from_json(value: &Value) -> Option<Self> {
+        let array = |key: &str| value.get(key)?.as_array();
+
+        Some(Self {
+            indices: array("indices")?
+                .iter()
+                .map(|x| x.as_u64().map(|x| x as usize))
+                .collect::<Option<_>>()?,
+            values: array("values")?
+                .iter()
+                .map(|x| x.as_f64().map(|x| x as f32))
+                .collect::<Option<_>>()?,
+        })
+    }
+}
+
+/// 제목을 TF-IDF 벡터로 바꿈
+///
+/// 한국어는 Kiwi 형태소, 외국어는 정규화한 단어와 문자 n-gram을 단어로 씀
+pub struct Vectorizer {
+    counter: CountVectorizer,
+    /// 단어 순서대로의 IDF
+    idf: Vec<f32>,
+}
+
+/// 제목의 단어
+///
+/// 토크나이저가 두 글자 이상의 단어 문자만 단어로 보므로 종류를 앞에 붙임 (`k_집` `w_kanojo` `c__ka`)
+pub fn terms(kiwi: &Kiwi, split: &Split) -> Result<Vec<String>, Box<dyn std::error::Error>> {
+    let mut res = content_morphemes(
+        kiwi,
+        &split.normalized.korean,
+        &EXTRA_TAGS,
+        str::to_lowercase,
+    )?
+    .into_iter()
+    .map(|x| format!("k_{x}"))
+    .collect::<Vec<_>>();
+
+    for word in split
+        .normalized
+        .foreign
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|x| !x.is_empty())
+    {
+        res.push(format!("w_{word}"));
+
+        // 단어 경계를 `_`로 나타냄
+        let chars = format!("_{word}_").chars().collect::<Vec<_>>();
+        for w in chars.windows(CHAR_NGRAM) {
+            res.push(format!("c_{}", w.iter().collect::<String>()));
+        }
+    }
+
+    Ok(res)
+}
+
+fn documents(kiwi: &Kiwi, splits: &[&Split]) -> Result<Array1<String>, Box<dyn std::error::Error>> {
+    let docs = splits
+        .iter()
+        .map(|x| Ok(terms(kiwi, x)?.join(" ")))
+        .collect::<Result<Vec<_>, Box<dyn std::error::Error>>>()?;
+
+    Ok(Array1::from(docs))
+}
+
+impl Vectorizer {
+    /// 제목들로 단어 목록과 IDF를 만듦
+    pub fn fit(kiwi: &Kiwi, items: &[(String, Split)]) -> Result<Self, Box<dyn std::error::Error>> {
+        let splits = items.iter().map(|(_, x)| x).collect::<Vec<_>>();
+        let docs = documents(kiwi, &splits)?;
+
+        let counter = CountVectorizer::params().fit(&docs)?;
+        let counts = counter.transform(&docs)?;
+
+        let mut df = vec![0u32; counter.nentries()];
+        for row in counts.outer_iterator() {
+            for (i, _) in row.iter() {
+                df[i] += 1;
+            }
+        }
+
+        // 부드러운 IDF
+        let n = splits.len() as f32;
+        let idf = df
+            .into_iter()
+            .map(|df| ((1.0 + n) / (1.0 + df as f32)).ln() + 1.0)
+            .collect();
+
+        Ok(Self { counter, idf })
+    }
+
+    /// 벡터의 차원
+    pub fn len(&self) -> usize {
+        self.idf.len()
+    }
+
+    pub fn transform(
+        &self,
+        kiwi: &Kiwi,
+        split: &Split,
+    ) -> Result<SparseVector, Box<dyn std::error::Error>> {
+        let counts = self.counter.transform(&documents(kiwi, &[split])?)?;
+
+        let mut res = SparseVector::default();
+        for row in counts.outer_iterator() {
+            for (i, count) in row.iter() {
+                res.indices.push(i);
+                res.values.push(*count as f32 * self.idf[i]);
+            }
+        }
+        res.normalize();
+
+        Ok(res)
+    }
+
+    pub fn to_json(&self) -> Value {
+        json!({
+            "vocabulary": self.counter.vocabulary(),
+            "idf": self.idf,
+        })
+    }
+
+    /// `to_json`으로 만든 값을 읽음
+    pub fn from_json(value: &Value) -> Result<Self, Box<dyn std::error::Error>> {
+        let strings = |key: &str| {
+            value
+                .get(key)
+                .and_then(Value::as_array)
+                .ok_or_else(|| format!("missing {key}"))
+        };
+
+        let vocabulary = strings("vocabulary")?
+            .iter()
+            .map(|x| x.as_str().ok_or("invalid vocabulary"))
+            .collect::<Result<Vec<_>, _>>()?;
+        let idf = strings("idf")?
+            .iter()
+            .map(|x| x.as_f64().map(|x| x as f32).ok_or("invalid idf"))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        if vocabulary.len() != idf.len() {
+            return Err("vocabulary and idf have different lengths".into());
+        }
+
+        Ok(Self {
+            counter: CountVectorizer::params().fit_vocabulary(&vocabulary)?,
+            idf,
+        })
+    }
+}
+
+#[test]
+fn test_vectorizer() -> Result<(), Box<dyn std::error::Error>> {
+    use rkiwi::KiwiBuilder;
+
+    let kiwi = KiwiBuilder::new(None, Default::default())?.build(None, None)?;
+
+    let items = [
+        "Kanojo no Himitsu | 여자친구 비밀",
+        "Kanojo no Himitsu 2",
+        "Natsuyasumi no Bus Tour",
+        "여름방학 버스 투어",
+    ]
+    .into_iter()
+    .map(|x| Ok((x.to_owned(), crate::process(&kiwi, x)?)))
+    .collect::<Result<Vec<_>, Box<dyn std::error::Error>>>()?;
+
+    let vectorizer = Vectorizer::fit(&kiwi, &items)?;
+    assert!(vectorizer.len() > 0);
+
+    let vectors = items
+        .iter()
+        .map(|(_, x)| vectorizer.transform(&kiwi, x))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let norm = vectors[0].dot(&vectors[0]);
+    assert!((norm - 1.0).abs() < 1e-5);
+    assert!(vectors[0].dot(&vectors[1]) > vectors[0].dot(&vectors[2]));
+    assert_eq!(vectors[2].dot(&vectors[3]), 0.0);
+
+    // 저장한 단어 목록으로 같은 벡터를 만듦
+    let loaded = Vectorizer::from_json(&vectorizer.to_json())?;
+    assert_eq!(loaded.len(), vectorizer.len());
+    assert_eq!(loaded.transform(&kiwi, &items[1].1)?, vectors[1]);
+
+    Ok(())
+}
@@ -0,0 +1,188 @@
+use std::ops::Range;
+
+use rkiwi::{POSTag, analyzed::Token};
+use widestring::{U16Str, U16String, u16str};
+
+use crate::{
+    bracket::Bracket,
+    is_pipe,
+    tag::{self, TagKind},
+};
+
+#[derive(Debug, Clone)]
+pub struct TrimOptions {
+    /// `/ Kareshi Mochi` 의 `/` 처럼 가장자리에 남은 구분자
+    pub separators: bool,
+    /// 짝이 없거나 짝이 다른 부분에 있는 괄호
+    pub orphan_brackets: bool,
+    /// `[korean]` 처럼 괄호로 감싸진 언어 표기
+    pub language_markers: bool,
+    /// `(decensored)` `[Digital]` 처럼 괄호로 감싸진 배포 태그
+    pub release_tags: bool,
+}
+
+impl Default for TrimOptions {
+    fn default() -> Self {
+        Self {
+            separators: true,
+            orphan_brackets: true,
+            language_markers: true,
+            release_tags: true,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TrimReason {
+    Separator,
+    OrphanBracket,
+    LanguageMarker,
+    ReleaseTag,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Trimmed {
+    pub reason: TrimReason,
+    pub text: String,
+    /// 원문 위치 (utf-16)
+    pub span: Range<usize>,
+}
+
+const SEPARATOR_CHARS: [&U16Str; 14] = [
+    u16str!("/"),
+    u16str!("／"),
+    u16str!("\\"),
+    u16str!("+"),
+    u16str!("＋"),
+    u16str!("&"),
+    u16str!(","),
+    u16str!("、"),
+    u16str!("·"),
+    u16str!(":"),
+    u16str!(";"),
+    u16str!("_"),
+    u16str!("="),
+    u16str!("•"),
+];
+
+fn is_separator(x: impl AsRef<U16Str>) -> bool {
+    let x = x.as_ref();
+    is_pipe(x) || SEPARATOR_CHARS.contains(&x)
+}
+
+#[derive(Clone, Copy)]
+enum Edge {
+    Start,
+    End,
+}
+
+/// 각 부분의 가장자리에 남은 구분자, 짝이 없는 괄호, 언어 표기와 배포 태그를 제거함
+///
+/// 제거된 문자열은 `removed`에 기록됨
+pub fn trim(
+    text: &U16Str,
+    xs: &[(U16String, Token)],
+    brackets: &[Bracket],
+    range: Range<usize>,
+    options: &TrimOptions,
+    removed: &mut Vec<Trimmed>,
+) -> Range<usize> {
+    let mut range = range;
+
+    // 한쪽 가장자리에서 더 이상 제거할 것이 없으면 반대쪽으로 넘어감
+    for edge in [Edge::Start, Edge::End] {
+        while let Some((reason, span)) = trim_once(text, xs, brackets, &range, options, edge) {
+            removed.push(Trimmed {
+                reason,
+                text: text[span.clone()].to_string_lossy(),
+                span: span.clone(),
+            });
+
+            match edge {
+                Edge::Start => range.start = span.end,
+                Edge::End => range.end = span.start,
+            }
+        }
+    }
+
+    range
+}
+
+fn trim_once(
+    text: &U16Str,
+    xs: &[(U16String, Token)],
+    brackets: &[Bracket],
+    range: &Range<usize>,
+    options: &TrimOptions,
+    edge: Edge,
+) -> Option<(TrimReason, Range<usize>)> {
+    let within = |span: &Range<usize>| range.start <= span.start && span.end <= range.end;
+
+    let mut part = xs
+        .iter()
+        .enumerate()
+        .filter(|(_, (_, t))| within(&(t.chr_position..t.chr_position + t.length)));
+
+    let (i, (form, token)) = match edge {
+        Edge::Start => part.next()?,
+        Edge::End => part.next_back()?,
+    };
+    let span = token.chr_position..token.chr_position + token.length;
+
+    if options.separators && is_separator(form) {
+        return Some((TrimReason::Separator, span));
+    }
+
+    // 부제목을 감싸는 `~` `-` 중 짝이 없는 것
+    if options.separators && token.tag == POSTag::SO {
+        let paired = xs
+            .iter()
+            .enumerate()
+            .filter(|(j, (_, t))| *j != i && within(&(t.chr_position..t.chr_position + t.length)))
+            .any(|(_, (f, _))| f == form);
+
+        if !paired {
+            return Some((TrimReason::Separator, span));
+        }
+    }
+
+    // `[Korean` `Korean]` 처럼 한쪽 괄호가 없는 언어 표기
+    if options.language_markers {
+        let broken = tag::broken_language_markers(xs, brackets)
+            .into_iter()
+            .map(|(tokens, _)| {
+                let (start, end) = (&xs[tokens.start].1, &xs[tokens.end - 1].1);
+                start.chr_position..end.chr_position + end.length
+            })
+            .find(|span| {
+                within(span)
+                    && (span.start == token.chr_position
+                        || span.end == token.chr_position + token.length)
+            });
+
+        if let Some(span) = broken {
+            return Some((TrimReason::LanguageMarker, span));
+        }
+    }
+
+    let bracket = brackets
+        .iter()
+        .find(|x| x.open == Some(i) || x.close == Some(i))?;
+
+    if !bracket.is_matched() || !within(&bracket.span) {
+        return options
+            .orphan_brackets
+            .then_some((TrimReason::OrphanBracket, span));
+    }
+
+    let inner = text[bracket.span.start + 1..bracket.span.end - 1].to_string_lossy();
+
+    match tag::parse(&inner)? {
+        TagKind::Language(_) => options
+            .language_markers
+            .then_some((TrimReason::LanguageMarker, bracket.span.clone())),
+        _ => options
+            .release_tags
+            .then_some((TrimReason::ReleaseTag, bracket.span.clone())),
+    }
+}